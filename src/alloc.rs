@@ -0,0 +1,84 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering::Relaxed};
+
+/// Global allocator forwarding to [`System`] that, once [`start`] is called,
+/// counts allocations, allocated bytes and the peak of live bytes. Live bytes
+/// are relative to the moment counting started, so memory freed during a run
+/// that was allocated before it can make them dip below zero.
+pub struct CountingAlloc;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub allocations: usize,
+    pub allocated: usize,
+    pub peak: usize,
+}
+
+/// Counts an allocation that adds `allocated` bytes to the total and changes
+/// the live bytes by `delta`.
+fn record(allocated: usize, delta: isize) {
+    ALLOCATIONS.fetch_add(1, Relaxed);
+    ALLOCATED.fetch_add(allocated, Relaxed);
+    let live = LIVE.fetch_add(delta, Relaxed) + delta;
+    PEAK.fetch_max(live, Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() && ENABLED.load(Relaxed) {
+            record(layout.size(), layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() && ENABLED.load(Relaxed) {
+            record(layout.size(), layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if ENABLED.load(Relaxed) {
+            LIVE.fetch_sub(layout.size() as isize, Relaxed);
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() && ENABLED.load(Relaxed) {
+            // Only the growth counts towards the bytes allocated.
+            record(
+                new_size.saturating_sub(layout.size()),
+                new_size as isize - layout.size() as isize,
+            );
+        }
+        new_ptr
+    }
+}
+
+pub fn start() {
+    ALLOCATIONS.store(0, Relaxed);
+    ALLOCATED.store(0, Relaxed);
+    LIVE.store(0, Relaxed);
+    PEAK.store(0, Relaxed);
+    ENABLED.store(true, Relaxed);
+}
+
+pub fn stop() -> Stats {
+    ENABLED.store(false, Relaxed);
+    Stats {
+        allocations: ALLOCATIONS.load(Relaxed),
+        allocated: ALLOCATED.load(Relaxed),
+        peak: PEAK.load(Relaxed).max(0) as usize,
+    }
+}
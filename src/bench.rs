@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use itertools::Itertools;
+
+use aoc2023::registry::{self, DAYS};
+
+pub const BENCHMARKS_PATH: &str = "benchmarks.txt";
+
+/// Result of benchmarking a single (day, part), as stored in `benchmarks.txt`.
+pub struct Entry {
+    pub median_ns: u128,
+    pub answer: String,
+}
+
+pub type Benchmarks = BTreeMap<(usize, usize), Entry>;
+
+pub fn load(path: &str) -> Benchmarks {
+    let mut result = Benchmarks::new();
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return result;
+    };
+    for line in content.lines().filter(|l| !l.trim().is_empty() && !l.starts_with('#')) {
+        let [day, part, median_ns, answer] = line.splitn(4, ' ').collect_vec()[..] else {
+            panic!("malformed line in {}: {}", path, line);
+        };
+        result.insert(
+            (day.parse().unwrap(), part.parse().unwrap()),
+            Entry {
+                median_ns: median_ns.parse().unwrap(),
+                answer: answer.to_string(),
+            },
+        );
+    }
+    result
+}
+
+pub fn save(path: &str, benchmarks: &Benchmarks) {
+    let mut content = String::from("# day part median_ns answer\n");
+    for ((day, part), entry) in benchmarks {
+        content += &format!("{:02} {} {} {}\n", day, part, entry.median_ns, entry.answer);
+    }
+    std::fs::write(path, content).unwrap_or_else(|e| panic!("couldn't write {}: {}", path, e));
+}
+
+fn median_ns(mut samples: Vec<u128>) -> u128 {
+    samples.sort_unstable();
+    samples[samples.len() / 2]
+}
+
+/// Runs every implemented part whose input is available `runs` times and stores
+/// the median runtime together with the produced answer. Days without an input
+/// keep whatever was previously recorded for them.
+pub fn run(runs: usize) {
+    assert!(runs > 0, "bench needs at least one run");
+    let mut benchmarks = load(BENCHMARKS_PATH);
+    for (i, day) in DAYS.iter().enumerate() {
+        let Ok(input) = std::fs::read_to_string(registry::input_path(i + 1)) else {
+            continue;
+        };
+        for (part, solution) in day.implemented() {
+            let mut samples = Vec::with_capacity(runs);
+            let mut answer = String::new();
+            for _ in 0..runs {
+                let start = Instant::now();
+                answer = solution(&input);
+                samples.push(start.elapsed().as_nanos());
+            }
+            let entry = Entry {
+                median_ns: median_ns(samples),
+                answer,
+            };
+            println!("day{:02} p{} = {} ({} ns)", i + 1, part, entry.answer, entry.median_ns);
+            benchmarks.insert((i + 1, part), entry);
+        }
+    }
+    save(BENCHMARKS_PATH, &benchmarks);
+}
@@ -0,0 +1,774 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Magnitudes are little-endian base 2^32 digits without leading zeros, so
+/// zero is the empty vector and equal numbers have equal digits.
+type Digits = Vec<u32>;
+
+fn trim(mut digits: Digits) -> Digits {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    digits
+}
+
+fn cmp_digits(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_digits(a: &[u32], b: &[u32]) -> Digits {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &x) in long.iter().enumerate() {
+        let sum = x as u64 + short.get(i).copied().unwrap_or(0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+/// `a - b`, which must not be negative.
+fn sub_digits(a: &[u32], b: &[u32]) -> Digits {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &x) in a.iter().enumerate() {
+        let mut diff = x as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        borrow = i64::from(diff < 0);
+        if diff < 0 {
+            diff += 1 << 32;
+        }
+        result.push(diff as u32);
+    }
+    debug_assert_eq!(borrow, 0, "subtracting a larger magnitude");
+    trim(result)
+}
+
+fn mul_digits(a: &[u32], b: &[u32]) -> Digits {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let product = x as u64 * y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = product as u32;
+            carry = product >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    trim(result)
+}
+
+/// Quotient and remainder of dividing by a single digit.
+fn div_rem_small(a: &[u32], divisor: u32) -> (Digits, u32) {
+    let mut quotient = vec![0; a.len()];
+    let mut remainder = 0u64;
+    for (i, &x) in a.iter().enumerate().rev() {
+        let current = (remainder << 32) | x as u64;
+        quotient[i] = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+    (trim(quotient), remainder as u32)
+}
+
+/// Schoolbook binary long division, `b` must not be zero.
+fn div_rem_digits(a: &[u32], b: &[u32]) -> (Digits, Digits) {
+    if cmp_digits(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+    if let [divisor] = b {
+        let (quotient, remainder) = div_rem_small(a, *divisor);
+        return (quotient, trim(vec![remainder]));
+    }
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder: Digits = Vec::new();
+    for bit in (0..a.len() * 32).rev() {
+        // remainder = remainder * 2 + next bit of `a`
+        let mut carry = (a[bit / 32] >> (bit % 32)) & 1;
+        for digit in remainder.iter_mut() {
+            let next = *digit >> 31;
+            *digit = (*digit << 1) | carry;
+            carry = next;
+        }
+        if carry > 0 {
+            remainder.push(carry);
+        }
+        if cmp_digits(&remainder, b) != Ordering::Less {
+            remainder = sub_digits(&remainder, b);
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    (trim(quotient), remainder)
+}
+
+/// Arbitrary-precision signed integer. Division truncates towards zero like
+/// the primitive integers do, and the remainder takes the dividend's sign.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Digits,
+}
+
+impl BigInt {
+    fn from_parts(negative: bool, magnitude: Digits) -> Self {
+        let magnitude = trim(magnitude);
+        BigInt {
+            negative: negative && !magnitude.is_empty(),
+            magnitude,
+        }
+    }
+
+    pub fn zero() -> Self {
+        BigInt::default()
+    }
+
+    pub fn one() -> Self {
+        BigInt::from(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// -1, 0 or 1.
+    pub fn signum(&self) -> i32 {
+        match (self.negative, self.is_zero()) {
+            (true, _) => -1,
+            (false, true) => 0,
+            (false, false) => 1,
+        }
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::from_parts(false, self.magnitude.clone())
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> BigInt {
+        let (mut base, mut result) = (self.clone(), BigInt::one());
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            base = &base * &base;
+            exponent >>= 1;
+        }
+        result
+    }
+
+    /// Truncated quotient and remainder, panics if `other` is zero.
+    pub fn div_rem(&self, other: &BigInt) -> (BigInt, BigInt) {
+        assert!(!other.is_zero(), "attempt to divide by zero");
+        let (quotient, remainder) = div_rem_digits(&self.magnitude, &other.magnitude);
+        (
+            BigInt::from_parts(self.negative != other.negative, quotient),
+            BigInt::from_parts(self.negative, remainder),
+        )
+    }
+
+    /// Greatest common divisor, never negative. `gcd(0, 0)` is 0.
+    pub fn gcd(&self, other: &BigInt) -> BigInt {
+        let (mut a, mut b) = (self.abs(), other.abs());
+        while !b.is_zero() {
+            let r = a.div_rem(&b).1;
+            (a, b) = (b, r);
+        }
+        a
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> Self {
+        let mut big = BigInt::from(value.unsigned_abs());
+        big.negative = value < 0;
+        big
+    }
+}
+
+impl From<u128> for BigInt {
+    fn from(mut value: u128) -> Self {
+        let mut magnitude = Vec::new();
+        while value > 0 {
+            magnitude.push(value as u32);
+            value >>= 32;
+        }
+        BigInt::from_parts(false, magnitude)
+    }
+}
+
+macro_rules! from_primitive {
+    ($wide:ty: $($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(value: $t) -> Self {
+                BigInt::from(value as $wide)
+            }
+        }
+    )*};
+}
+
+from_primitive!(i128: i8, i16, i32, i64, isize);
+from_primitive!(u128: u8, u16, u32, u64, usize);
+
+impl TryFrom<&BigInt> for i128 {
+    type Error = String;
+
+    fn try_from(value: &BigInt) -> Result<Self, Self::Error> {
+        let too_big = || format!("{} does not fit in i128", value);
+        if value.magnitude.len() > 4 {
+            return Err(too_big());
+        }
+        let magnitude = value
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0u128, |acc, &digit| (acc << 32) | digit as u128);
+        if value.negative {
+            0i128.checked_sub_unsigned(magnitude).ok_or_else(too_big)
+        } else {
+            i128::try_from(magnitude).map_err(|_| too_big())
+        }
+    }
+}
+
+impl TryFrom<&BigInt> for i64 {
+    type Error = String;
+
+    fn try_from(value: &BigInt) -> Result<Self, Self::Error> {
+        let wide = i128::try_from(value)?;
+        i64::try_from(wide).map_err(|_| format!("{} does not fit in i64", value))
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = String;
+
+    /// Decimal digits with an optional leading sign.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("{:?} is not an integer", s));
+        }
+        let mut magnitude = Vec::new();
+        for chunk in digits.as_bytes().chunks(9) {
+            let chunk = std::str::from_utf8(chunk).unwrap();
+            let scale = 10u32.pow(chunk.len() as u32);
+            let mut carry = chunk.parse::<u32>().unwrap() as u64;
+            for digit in magnitude.iter_mut() {
+                let next = *digit as u64 * scale as u64 + carry;
+                *digit = next as u32;
+                carry = next >> 32;
+            }
+            if carry > 0 {
+                magnitude.push(carry as u32);
+            }
+        }
+        Ok(BigInt::from_parts(negative, magnitude))
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Peel off nine decimal digits at a time, least significant first.
+        let mut chunks = Vec::new();
+        let mut rest = self.magnitude.clone();
+        while !rest.is_empty() {
+            let (quotient, chunk) = div_rem_small(&rest, 1_000_000_000);
+            chunks.push(chunk);
+            rest = quotient;
+        }
+        let mut digits = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&format!("{:09}", chunk));
+        }
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_digits(&self.magnitude, &other.magnitude),
+            (true, true) => cmp_digits(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(
+                self.negative,
+                add_digits(&self.magnitude, &other.magnitude),
+            );
+        }
+        match cmp_digits(&self.magnitude, &other.magnitude) {
+            Ordering::Less => BigInt::from_parts(
+                other.negative,
+                sub_digits(&other.magnitude, &self.magnitude),
+            ),
+            _ => BigInt::from_parts(self.negative, sub_digits(&self.magnitude, &other.magnitude)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            mul_digits(&self.magnitude, &other.magnitude),
+        )
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).1
+    }
+}
+
+/// Implements the owned flavours of a binary operator and its compound
+/// assignment on top of the `&T op &T` one.
+macro_rules! forward_binop {
+    ($t:ty, $op:ident, $method:ident $(, $assign:ident, $assign_method:ident)?) => {
+        impl $op for $t {
+            type Output = $t;
+
+            fn $method(self, other: $t) -> $t {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $op<&$t> for $t {
+            type Output = $t;
+
+            fn $method(self, other: &$t) -> $t {
+                (&self).$method(other)
+            }
+        }
+
+        impl $op<$t> for &$t {
+            type Output = $t;
+
+            fn $method(self, other: $t) -> $t {
+                self.$method(&other)
+            }
+        }
+
+        $(
+            impl $assign<&$t> for $t {
+                fn $assign_method(&mut self, other: &$t) {
+                    *self = (&*self).$method(other);
+                }
+            }
+
+            impl $assign for $t {
+                fn $assign_method(&mut self, other: $t) {
+                    *self = (&*self).$method(&other);
+                }
+            }
+        )?
+    };
+}
+
+forward_binop!(BigInt, Add, add, AddAssign, add_assign);
+forward_binop!(BigInt, Sub, sub, SubAssign, sub_assign);
+forward_binop!(BigInt, Mul, mul, MulAssign, mul_assign);
+forward_binop!(BigInt, Div, div);
+forward_binop!(BigInt, Rem, rem);
+
+impl Sum for BigInt {
+    fn sum<I: Iterator<Item = BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::zero(), |acc, v| acc + v)
+    }
+}
+
+impl<'a> Sum<&'a BigInt> for BigInt {
+    fn sum<I: Iterator<Item = &'a BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::zero(), |acc, v| acc + v)
+    }
+}
+
+/// Exact fraction, always kept in lowest terms with a positive denominator.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: BigInt,
+    denom: BigInt,
+}
+
+impl Rational {
+    /// `numer / denom`, panics if `denom` is zero.
+    pub fn new(numer: BigInt, denom: BigInt) -> Self {
+        assert!(!denom.is_zero(), "rational with a zero denominator");
+        let g = numer.gcd(&denom);
+        let (mut numer, mut denom) = (&numer / &g, &denom / &g);
+        if denom.is_negative() {
+            (numer, denom) = (-numer, -denom);
+        }
+        Rational { numer, denom }
+    }
+
+    pub fn zero() -> Self {
+        Rational::from(BigInt::zero())
+    }
+
+    pub fn numer(&self) -> &BigInt {
+        &self.numer
+    }
+
+    pub fn denom(&self) -> &BigInt {
+        &self.denom
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numer.is_zero()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == BigInt::one()
+    }
+
+    /// `1 / self`, panics if `self` is zero.
+    pub fn recip(&self) -> Rational {
+        Rational::new(self.denom.clone(), self.numer.clone())
+    }
+
+    /// Largest integer not above `self`.
+    pub fn floor(&self) -> BigInt {
+        let (quotient, remainder) = self.numer.div_rem(&self.denom);
+        if remainder.is_negative() {
+            quotient - BigInt::one()
+        } else {
+            quotient
+        }
+    }
+}
+
+impl From<BigInt> for Rational {
+    fn from(value: BigInt) -> Self {
+        Rational {
+            numer: value,
+            denom: BigInt::one(),
+        }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Rational::from(BigInt::from(value))
+    }
+}
+
+impl FromStr for Rational {
+    type Err = String;
+
+    /// An integer, or two of them separated by `/`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('/') {
+            Some((numer, denom)) => {
+                let denom = denom.parse::<BigInt>()?;
+                if denom.is_zero() {
+                    return Err(format!("{:?} has a zero denominator", s));
+                }
+                Ok(Rational::new(numer.parse()?, denom))
+            }
+            None => Ok(Rational::from(s.parse::<BigInt>()?)),
+        }
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.numer * &other.denom).cmp(&(&other.numer * &self.denom))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            numer: -&self.numer,
+            denom: self.denom.clone(),
+        }
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        -&self
+    }
+}
+
+impl Add for &Rational {
+    type Output = Rational;
+
+    fn add(self, other: &Rational) -> Rational {
+        Rational::new(
+            &self.numer * &other.denom + &other.numer * &self.denom,
+            &self.denom * &other.denom,
+        )
+    }
+}
+
+impl Sub for &Rational {
+    type Output = Rational;
+
+    fn sub(self, other: &Rational) -> Rational {
+        self + &-other
+    }
+}
+
+impl Mul for &Rational {
+    type Output = Rational;
+
+    fn mul(self, other: &Rational) -> Rational {
+        Rational::new(&self.numer * &other.numer, &self.denom * &other.denom)
+    }
+}
+
+impl Div for &Rational {
+    type Output = Rational;
+
+    /// Panics if `other` is zero.
+    fn div(self, other: &Rational) -> Rational {
+        assert!(!other.is_zero(), "attempt to divide by zero");
+        Rational::new(&self.numer * &other.denom, &self.denom * &other.numer)
+    }
+}
+
+forward_binop!(Rational, Add, add, AddAssign, add_assign);
+forward_binop!(Rational, Sub, sub, SubAssign, sub_assign);
+forward_binop!(Rational, Mul, mul, MulAssign, mul_assign);
+forward_binop!(Rational, Div, div);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    fn rational(numer: i64, denom: i64) -> Rational {
+        Rational::new(BigInt::from(numer), BigInt::from(denom))
+    }
+
+    #[test]
+    fn carries_and_borrows_across_limbs() {
+        assert_eq!(
+            BigInt::from(u32::MAX) + BigInt::one(),
+            BigInt::from(1u64 << 32)
+        );
+        assert_eq!(
+            BigInt::from(u64::MAX) + BigInt::one(),
+            big("18446744073709551616")
+        );
+        assert_eq!(
+            big("18446744073709551616") - BigInt::one(),
+            BigInt::from(u64::MAX)
+        );
+        assert_eq!(
+            BigInt::from(1u128 << 64) - BigInt::from(1u128 << 65),
+            -BigInt::from(1u128 << 64)
+        );
+        assert_eq!(
+            BigInt::from(1u128 << 96) - BigInt::one(),
+            big("79228162514264337593543950335")
+        );
+        assert_eq!(
+            BigInt::from(u64::MAX) * BigInt::from(u64::MAX),
+            big("340282366920938463426481119284349108225")
+        );
+    }
+
+    #[test]
+    fn multiplies_with_signs() {
+        assert_eq!(BigInt::from(-3) * BigInt::from(4), BigInt::from(-12));
+        assert_eq!(BigInt::from(3) * BigInt::from(-4), BigInt::from(-12));
+        assert_eq!(BigInt::from(-3) * BigInt::from(-4), BigInt::from(12));
+        let zero = BigInt::from(-5) * BigInt::zero();
+        assert!(zero.is_zero() && !zero.is_negative());
+        assert_eq!(zero.to_string(), "0");
+    }
+
+    #[test]
+    fn division_truncates_and_remainder_follows_dividend() {
+        let div_rem = |a: i64, b: i64| {
+            let (q, r) = BigInt::from(a).div_rem(&BigInt::from(b));
+            (i64::try_from(&q).unwrap(), i64::try_from(&r).unwrap())
+        };
+        assert_eq!(div_rem(7, 2), (3, 1));
+        assert_eq!(div_rem(-7, 2), (-3, -1));
+        assert_eq!(div_rem(7, -2), (-3, 1));
+        assert_eq!(div_rem(-7, -2), (3, -1));
+        assert_eq!(div_rem(6, -3), (-2, 0));
+
+        let (q, r) = BigInt::from(1u128 << 100).div_rem(&BigInt::from(3));
+        assert_eq!(q, big("422550200076076467165567735125"));
+        assert_eq!(r, BigInt::one());
+        let a = -big("1000000000000000000000000000007");
+        let b = big("1000000000003");
+        assert_eq!(&a / &b, big("-999999999997000000"));
+        assert_eq!(&a % &b, big("-9000007"));
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn rejects_division_by_zero() {
+        let _ = BigInt::one() / BigInt::zero();
+    }
+
+    #[test]
+    fn gcd_and_pow() {
+        assert_eq!(BigInt::from(-12).gcd(&BigInt::from(18)), BigInt::from(6));
+        assert_eq!(BigInt::zero().gcd(&BigInt::zero()), BigInt::zero());
+        assert_eq!(BigInt::from(2).pow(100), BigInt::from(1u128 << 100));
+        assert_eq!(BigInt::from(-3).pow(3), BigInt::from(-27));
+    }
+
+    #[test]
+    fn parses_and_displays_round_trip() {
+        for s in [
+            "0",
+            "-1",
+            "4294967296",
+            "-340282366920938463426481119284349108225",
+        ] {
+            assert_eq!(big(s).to_string(), s);
+        }
+        assert_eq!(big("+12").to_string(), "12");
+        assert_eq!(big("-0").to_string(), "0");
+        assert_eq!(big("000123").to_string(), "123");
+        assert_eq!(format!("{:>6}", BigInt::from(-42)), "   -42");
+        assert!("".parse::<BigInt>().is_err());
+        assert!("-".parse::<BigInt>().is_err());
+        assert!("12a".parse::<BigInt>().is_err());
+    }
+
+    #[test]
+    fn converts_to_primitives_only_when_in_range() {
+        assert_eq!(i128::try_from(&BigInt::from(i128::MIN)), Ok(i128::MIN));
+        assert!(i128::try_from(&(BigInt::from(i128::MAX) + BigInt::one())).is_err());
+        assert!(i64::try_from(&BigInt::from(i64::MIN as i128 - 1)).is_err());
+    }
+
+    #[test]
+    fn rationals_are_normalised() {
+        let r = rational(6, -4);
+        assert_eq!(
+            (r.numer(), r.denom()),
+            (&BigInt::from(-3), &BigInt::from(2))
+        );
+        let r = rational(-10, -15);
+        assert_eq!((r.numer(), r.denom()), (&BigInt::from(2), &BigInt::from(3)));
+        let zero = rational(0, -5);
+        assert_eq!(
+            (zero.numer(), zero.denom()),
+            (&BigInt::zero(), &BigInt::one())
+        );
+        assert_eq!(rational(1, 2) + rational(1, 3), rational(5, 6));
+        assert_eq!(rational(1, 2) - rational(5, 6), rational(-1, 3));
+        assert_eq!(rational(2, 3) * rational(-3, 4), rational(-1, 2));
+        assert_eq!(rational(1, 2) / rational(-1, 4), rational(-2, 1));
+        assert!(rational(4, 2).is_integer());
+        assert_eq!(rational(-7, 2).floor(), BigInt::from(-4));
+        assert_eq!(rational(7, 2).floor(), BigInt::from(3));
+        assert!(rational(-1, 2) < rational(1, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "zero denominator")]
+    fn rejects_zero_denominator() {
+        rational(1, 0);
+    }
+
+    #[test]
+    fn parses_and_displays_rationals() {
+        assert_eq!("-3/6".parse::<Rational>().unwrap().to_string(), "-1/2");
+        assert_eq!("3/-6".parse::<Rational>().unwrap().to_string(), "-1/2");
+        assert_eq!("4/2".parse::<Rational>().unwrap().to_string(), "2");
+        assert_eq!("-7".parse::<Rational>().unwrap(), rational(-7, 1));
+        assert!("1/0".parse::<Rational>().is_err());
+        assert!("1/x".parse::<Rational>().is_err());
+    }
+}
@@ -0,0 +1,306 @@
+use std::fmt::Display;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+
+const BITS: usize = u64::BITS as usize;
+
+/// Rectangular set of cells packed one bit per cell, each row in its own run
+/// of `u64` words with column `x` at bit `x % 64` of word `x / 64`. Bits past
+/// the last column are always clear, so whole words can be counted and compared.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// An empty `width` by `height` grid.
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(BITS);
+        BitGrid {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+
+    /// The cells of `grid` that satisfy `predicate`.
+    pub fn from_grid<T>(grid: &Grid<T>, mut predicate: impl FnMut(&T) -> bool) -> Self {
+        let mut bits = BitGrid::new(grid.width(), grid.height());
+        for (p, v) in grid.iter() {
+            if predicate(v) {
+                bits.insert(p);
+            }
+        }
+        bits
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    /// Word index and bit mask of `p`, which must be in bounds.
+    fn locate(&self, p: Point) -> (usize, u64) {
+        assert!(
+            self.contains(p),
+            "{:?} is outside of a {}x{} grid",
+            p,
+            self.width,
+            self.height
+        );
+        let (x, y) = (p.x as usize, p.y as usize);
+        (y * self.stride + x / BITS, 1 << (x % BITS))
+    }
+
+    /// Whether `p` is set, out of bounds positions never are.
+    pub fn get(&self, p: Point) -> bool {
+        self.contains(p) && {
+            let (i, mask) = self.locate(p);
+            self.words[i] & mask != 0
+        }
+    }
+
+    /// Sets `p`, returns whether it was clear before.
+    pub fn insert(&mut self, p: Point) -> bool {
+        let (i, mask) = self.locate(p);
+        let was_clear = self.words[i] & mask == 0;
+        self.words[i] |= mask;
+        was_clear
+    }
+
+    /// Clears `p`, returns whether it was set before.
+    pub fn remove(&mut self, p: Point) -> bool {
+        let (i, mask) = self.locate(p);
+        let was_set = self.words[i] & mask != 0;
+        self.words[i] &= !mask;
+        was_set
+    }
+
+    pub fn set(&mut self, p: Point, value: bool) {
+        if value {
+            self.insert(p);
+        } else {
+            self.remove(p);
+        }
+    }
+
+    pub fn toggle(&mut self, p: Point) {
+        let (i, mask) = self.locate(p);
+        self.words[i] ^= mask;
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Number of set cells.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Words of row `y`, the last one padded with clear bits.
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    /// Number of set cells in row `y`.
+    pub fn row_count(&self, y: usize) -> usize {
+        self.row(y).iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Column `x` packed the same way as a row, cell `y` at bit `y % 64` of word `y / 64`.
+    pub fn column(&self, x: usize) -> Vec<u64> {
+        assert!(x < self.width, "column {} of a {} wide grid", x, self.width);
+        let (word, shift) = (x / BITS, x % BITS);
+        let mut column = vec![0; self.height.div_ceil(BITS)];
+        for y in 0..self.height {
+            let bit = (self.words[y * self.stride + word] >> shift) & 1;
+            column[y / BITS] |= bit << (y % BITS);
+        }
+        column
+    }
+
+    /// Every set cell, row by row.
+    pub fn ones(&self) -> impl Iterator<Item = Point> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let (y, x0) = (i / self.stride, i % self.stride * BITS);
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                (rest != 0).then(|| {
+                    let bit = rest.trailing_zeros() as usize;
+                    rest &= rest - 1;
+                    Point::new((x0 + bit) as i64, y as i64)
+                })
+            })
+        })
+    }
+
+    /// Mask of the bits of a row's last word that hold cells.
+    fn last_word_mask(&self) -> u64 {
+        match self.width % BITS {
+            0 => !0,
+            used => (1 << used) - 1,
+        }
+    }
+
+    /// Clears the padding bits an operation may have set.
+    fn trim(&mut self) {
+        if self.stride == 0 {
+            return;
+        }
+        let mask = self.last_word_mask();
+        for row in self.words.chunks_mut(self.stride) {
+            row[row.len() - 1] &= mask;
+        }
+    }
+
+    /// Every set cell moved one step towards `dir`, the ones pushed over the edge are lost.
+    pub fn shifted(&self, dir: Direction) -> BitGrid {
+        let mut result = BitGrid::new(self.width, self.height);
+        let stride = self.stride;
+        if stride == 0 {
+            return result;
+        }
+        match dir {
+            Direction::Up if self.height > 0 => {
+                result.words[..stride * (self.height - 1)].copy_from_slice(&self.words[stride..]);
+            }
+            Direction::Down if self.height > 0 => {
+                result.words[stride..].copy_from_slice(&self.words[..stride * (self.height - 1)]);
+            }
+            Direction::Right => {
+                for (from, to) in self
+                    .words
+                    .chunks(stride)
+                    .zip(result.words.chunks_mut(stride))
+                {
+                    let mut carry = 0;
+                    for (w, t) in from.iter().zip(to.iter_mut()) {
+                        *t = (w << 1) | carry;
+                        carry = w >> (BITS - 1);
+                    }
+                }
+                result.trim();
+            }
+            Direction::Left => {
+                for (from, to) in self
+                    .words
+                    .chunks(stride)
+                    .zip(result.words.chunks_mut(stride))
+                {
+                    let mut carry = 0;
+                    for (w, t) in from.iter().zip(to.iter_mut()).rev() {
+                        *t = (w >> 1) | carry;
+                        carry = w << (BITS - 1);
+                    }
+                }
+            }
+            _ => (),
+        }
+        result
+    }
+
+    /// Cells set here but not in `other`.
+    pub fn and_not(&self, other: &BitGrid) -> BitGrid {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    fn zip_with(&self, other: &BitGrid, f: impl Fn(u64, u64) -> u64) -> BitGrid {
+        let mut result = self.clone();
+        result.zip_assign(other, f);
+        result
+    }
+
+    fn zip_assign(&mut self, other: &BitGrid, f: impl Fn(u64, u64) -> u64) {
+        assert!(
+            (self.width, self.height) == (other.width, other.height),
+            "combining a {}x{} grid with a {}x{} one",
+            self.width,
+            self.height,
+            other.width,
+            other.height
+        );
+        for (a, &b) in self.words.iter_mut().zip(&other.words) {
+            *a = f(*a, b);
+        }
+    }
+}
+
+macro_rules! bit_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident, $f:expr) => {
+        impl $op<&BitGrid> for &BitGrid {
+            type Output = BitGrid;
+
+            fn $method(self, other: &BitGrid) -> BitGrid {
+                self.zip_with(other, $f)
+            }
+        }
+
+        impl $assign<&BitGrid> for BitGrid {
+            fn $assign_method(&mut self, other: &BitGrid) {
+                self.zip_assign(other, $f)
+            }
+        }
+    };
+}
+
+bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| a & b);
+bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| a | b);
+bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| a ^ b);
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut result = self.clone();
+        for w in result.words.iter_mut() {
+            *w = !*w;
+        }
+        result.trim();
+        result
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height as i64 {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for x in 0..self.width as i64 {
+                write!(f, "{}", if self.get(Point::new(x, y)) { '#' } else { '.' })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn displays_like_a_grid() {
+        let grid = Grid::parse("#..\n.##", |c| c);
+        let bits = BitGrid::from_grid(&grid, |&c| c == '#');
+        assert_eq!(bits.to_string(), grid.to_string());
+        assert_eq!(bits.to_string(), "#..\n.##");
+    }
+}
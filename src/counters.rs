@@ -0,0 +1,31 @@
+use std::sync::Mutex;
+
+static COUNTERS: Mutex<Vec<(String, u64)>> = Mutex::new(Vec::new());
+
+/// Adds `n` to the counter called `name`, creating it on first use.
+/// Prefer the [`aoccount!`](crate::aoccount) macro, which compiles to nothing
+/// unless the `counters` feature is enabled.
+pub fn add(name: &str, n: u64) {
+    let mut counters = COUNTERS.lock().unwrap();
+    match counters.iter_mut().find(|(counter, _)| counter == name) {
+        Some((_, value)) => *value += n,
+        None => counters.push((name.to_string(), n)),
+    }
+}
+
+/// Returns every counter recorded so far, in first-use order, and resets them.
+pub fn take() -> Vec<(String, u64)> {
+    std::mem::take(&mut *COUNTERS.lock().unwrap())
+}
+
+#[macro_export]
+macro_rules! aoccount {
+    ($name:expr) => {
+        $crate::aoccount!($name, 1)
+    };
+    ($name:expr, $n:expr) => {
+        if cfg!(feature = "counters") {
+            $crate::counters::add(&$name, $n as u64);
+        }
+    };
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::zobrist::StateStore;
+
+/// Shape of the sequence `x0, step(x0), step(step(x0)), ...` of a
+/// deterministic state machine: `mu` states lead into a loop of `lambda` states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub mu: usize,
+    pub lambda: usize,
+}
+
+impl Cycle {
+    /// The index below `mu + lambda` holding the same state as index `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.mu {
+            n
+        } else {
+            self.mu + (n - self.mu) % self.lambda
+        }
+    }
+}
+
+/// Brent's algorithm, finds the cycle keeping only two states around, at
+/// the cost of stepping roughly `2 * (mu + lambda)` times.
+pub fn brent<S: Clone + PartialEq>(initial: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let (mut power, mut lambda) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare = step(&hare);
+        lambda += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..lambda {
+        hare = step(&hare);
+    }
+    let mut mu = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+    Cycle { mu, lambda }
+}
+
+/// State `n` of the sequence, stepping at most about `3 * (mu + lambda)` times.
+pub fn nth_state<S: Clone + PartialEq>(initial: &S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let cycle = brent(initial, &mut step);
+    let mut state = initial.clone();
+    for _ in 0..cycle.reduce(n) {
+        state = step(&state);
+    }
+    state
+}
+
+/// Every distinct state of a sequence, in order, with the cycle they form.
+pub struct History<S> {
+    pub cycle: Cycle,
+    states: Vec<S>,
+}
+
+impl<S> History<S> {
+    /// The first `mu + lambda` states, after which the sequence repeats.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    pub fn nth_state(&self, n: usize) -> &S {
+        &self.states[self.cycle.reduce(n)]
+    }
+}
+
+/// Finds the cycle by remembering every state, so it steps exactly
+/// `mu + lambda` times. Better than [`brent`] when stepping is expensive.
+pub fn find_hashed<S: Clone + Eq + Hash>(initial: S, mut step: impl FnMut(&S) -> S) -> History<S> {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+    loop {
+        if let Some(&mu) = seen.get(&state) {
+            let cycle = Cycle {
+                mu,
+                lambda: states.len() - mu,
+            };
+            return History { cycle, states };
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// Steps `state` in place until it is state `n` of its sequence, skipping
+/// whole loops once a fingerprint repeats. Only fingerprints are remembered,
+/// so no state is ever cloned or fully compared, and a fingerprint collision
+/// would go unnoticed. Returns the cycle if it was reached before `n`.
+pub fn fast_forward<S>(
+    state: &mut S,
+    n: usize,
+    mut step: impl FnMut(&mut S),
+    fingerprint: impl Fn(&S) -> u64,
+) -> Option<Cycle> {
+    let mut store = StateStore::new();
+    for i in 0..n {
+        if let Some(mu) = store.record(fingerprint(state), i) {
+            let cycle = Cycle { mu, lambda: i - mu };
+            for _ in 0..(n - i) % cycle.lambda {
+                step(state);
+            }
+            return Some(cycle);
+        }
+        step(state);
+    }
+    None
+}
@@ -0,0 +1,269 @@
+use std::collections::VecDeque;
+
+/// Directed graph over the nodes `0..len` with weighted edges, for
+/// dependency-shaped inputs. Nothing stops edges from forming a cycle, the
+/// queries that need an acyclic graph report one instead of an answer.
+#[derive(Debug, Clone, Default)]
+pub struct Dag {
+    successors: Vec<Vec<(usize, i64)>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl Dag {
+    pub fn new(len: usize) -> Self {
+        Dag {
+            successors: vec![Vec::new(); len],
+            predecessors: vec![Vec::new(); len],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.successors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.successors.is_empty()
+    }
+
+    /// Edge of weight 1 from `from` to `to`, meaning `to` depends on `from`.
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        self.add_weighted_edge(from, to, 1);
+    }
+
+    pub fn add_weighted_edge(&mut self, from: usize, to: usize, weight: i64) {
+        self.successors[from].push((to, weight));
+        self.predecessors[to].push(from);
+    }
+
+    pub fn successors(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.successors[node].iter().map(|&(to, _)| to)
+    }
+
+    pub fn predecessors(&self, node: usize) -> &[usize] {
+        &self.predecessors[node]
+    }
+
+    /// Kahn's algorithm, every node comes after all of its predecessors. If
+    /// there is no such order the error holds a cycle, each node having an
+    /// edge to the next and the last one to the first.
+    pub fn topological_order(&self) -> Result<Vec<usize>, Vec<usize>> {
+        let mut in_degree = self.predecessors.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut ready = (0..self.len())
+            .filter(|&n| in_degree[n] == 0)
+            .collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(node) = ready.pop_front() {
+            order.push(node);
+            for next in self.successors(node) {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    ready.push_back(next);
+                }
+            }
+        }
+        if order.len() == self.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle(&in_degree))
+        }
+    }
+
+    /// Nodes Kahn's algorithm couldn't place all still have a predecessor
+    /// among themselves, so walking backwards from one of them must loop.
+    fn find_cycle(&self, in_degree: &[usize]) -> Vec<usize> {
+        let mut seen_at = vec![usize::MAX; self.len()];
+        let mut walk = Vec::new();
+        let mut node = (0..self.len()).find(|&n| in_degree[n] > 0).unwrap();
+        while seen_at[node] == usize::MAX {
+            seen_at[node] = walk.len();
+            walk.push(node);
+            node = *self.predecessors[node]
+                .iter()
+                .find(|&&p| in_degree[p] > 0)
+                .unwrap();
+        }
+        let mut cycle = walk.split_off(seen_at[node]);
+        cycle.reverse();
+        cycle
+    }
+
+    /// Best total weight of a path from `source` to every node, `None` for
+    /// the ones it doesn't reach. `better` picks between two candidates.
+    fn best_paths(
+        &self,
+        source: usize,
+        better: impl Fn(i64, i64) -> i64,
+    ) -> Result<Vec<Option<i64>>, Vec<usize>> {
+        let mut best = vec![None; self.len()];
+        best[source] = Some(0);
+        for node in self.topological_order()? {
+            let Some(distance) = best[node] else {
+                continue;
+            };
+            for &(next, weight) in &self.successors[node] {
+                let candidate = distance + weight;
+                best[next] = Some(best[next].map_or(candidate, |b| better(b, candidate)));
+            }
+        }
+        Ok(best)
+    }
+
+    /// Heaviest path weight from `source` to every node it reaches.
+    pub fn longest_paths(&self, source: usize) -> Result<Vec<Option<i64>>, Vec<usize>> {
+        self.best_paths(source, i64::max)
+    }
+
+    /// Lightest path weight from `source` to every node it reaches, negative weights are fine.
+    pub fn shortest_paths(&self, source: usize) -> Result<Vec<Option<i64>>, Vec<usize>> {
+        self.best_paths(source, i64::min)
+    }
+
+    /// The graph with the same reachability and as few edges as possible:
+    /// an edge is dropped when its target is also reachable some longer way.
+    /// Parallel edges collapse into one, weights are kept.
+    pub fn transitive_reduction(&self) -> Result<Dag, Vec<usize>> {
+        let order = self.topological_order()?;
+        let words = self.len().div_ceil(64);
+        // reachable[n] has a bit per node reachable from `n` in one or more steps.
+        let mut reachable = vec![vec![0u64; words]; self.len()];
+        for &node in order.iter().rev() {
+            let mut reach = vec![0u64; words];
+            for next in self.successors(node) {
+                reach[next / 64] |= 1 << (next % 64);
+                for (r, w) in reach.iter_mut().zip(&reachable[next]) {
+                    *r |= w;
+                }
+            }
+            reachable[node] = reach;
+        }
+
+        let mut reduced = Dag::new(self.len());
+        for node in 0..self.len() {
+            let mut kept = Vec::new();
+            for &(to, weight) in &self.successors[node] {
+                let redundant = kept.contains(&to)
+                    || self.successors(node).any(|other| {
+                        other != to && reachable[other][to / 64] & (1 << (to % 64)) != 0
+                    });
+                if !redundant {
+                    kept.push(to);
+                    reduced.add_weighted_edge(node, to, weight);
+                }
+            }
+        }
+        Ok(reduced)
+    }
+
+    /// Nodes that lose all their predecessors, directly or through a chain,
+    /// once `node` is gone. Like bricks falling when a support is removed,
+    /// nodes without predecessors to begin with never count.
+    pub fn sole_dependents(&self, node: usize) -> Result<Vec<usize>, Vec<usize>> {
+        let mut gone = vec![false; self.len()];
+        gone[node] = true;
+        let mut dependents = Vec::new();
+        for n in self.topological_order()? {
+            let predecessors = &self.predecessors[n];
+            if n != node && !predecessors.is_empty() && predecessors.iter().all(|&p| gone[p]) {
+                gone[n] = true;
+                dependents.push(n);
+            }
+        }
+        Ok(dependents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The diamond 0 -> {1, 2} -> 3 with a shortcut 0 -> 3, a parallel
+    /// 1 -> 3, a tail 3 -> 4 and the lone node 5.
+    fn diamond() -> Dag {
+        let mut dag = Dag::new(6);
+        dag.add_weighted_edge(0, 1, 2);
+        dag.add_weighted_edge(0, 2, 5);
+        dag.add_weighted_edge(1, 3, 4);
+        dag.add_weighted_edge(2, 3, -1);
+        dag.add_weighted_edge(0, 3, 1);
+        dag.add_weighted_edge(1, 3, 7);
+        dag.add_weighted_edge(3, 4, 3);
+        dag
+    }
+
+    /// The diamond with 4 -> 1 closing the cycle 1 -> 3 -> 4 -> 1.
+    fn diamond_with_cycle() -> Dag {
+        let mut dag = diamond();
+        dag.add_edge(4, 1);
+        dag
+    }
+
+    fn edges(dag: &Dag) -> Vec<(usize, usize)> {
+        (0..dag.len())
+            .flat_map(|n| dag.successors(n).map(move |m| (n, m)))
+            .collect()
+    }
+
+    #[test]
+    fn topological_order_puts_predecessors_first() {
+        let dag = diamond();
+        let order = dag.topological_order().unwrap();
+        assert_eq!(order, [0, 5, 1, 2, 3, 4]);
+        for (from, to) in edges(&dag) {
+            let position = |n| order.iter().position(|&m| m == n).unwrap();
+            assert!(position(from) < position(to), "{} -> {}", from, to);
+        }
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        let dag = diamond_with_cycle();
+        let cycle = dag.topological_order().unwrap_err();
+        assert_eq!(cycle, [3, 4, 1]);
+        for (i, &node) in cycle.iter().enumerate() {
+            let next = cycle[(i + 1) % cycle.len()];
+            assert!(dag.successors(node).any(|n| n == next));
+        }
+        assert!(dag.longest_paths(0).is_err());
+        assert!(dag.shortest_paths(0).is_err());
+        assert!(dag.transitive_reduction().is_err());
+        assert!(dag.sole_dependents(0).is_err());
+    }
+
+    #[test]
+    fn longest_and_shortest_paths() {
+        let dag = diamond();
+        assert_eq!(
+            dag.longest_paths(0).unwrap(),
+            [Some(0), Some(2), Some(5), Some(9), Some(12), None]
+        );
+        assert_eq!(
+            dag.shortest_paths(0).unwrap(),
+            [Some(0), Some(2), Some(5), Some(1), Some(4), None]
+        );
+        assert_eq!(
+            dag.longest_paths(2).unwrap(),
+            [None, None, Some(0), Some(-1), Some(2), None]
+        );
+    }
+
+    #[test]
+    fn transitive_reduction_drops_shortcuts_and_parallel_edges() {
+        let reduced = diamond().transitive_reduction().unwrap();
+        assert_eq!(edges(&reduced), [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)]);
+        // The first of the parallel edges keeps its weight.
+        assert_eq!(
+            reduced.longest_paths(0).unwrap(),
+            [Some(0), Some(2), Some(5), Some(6), Some(9), None]
+        );
+    }
+
+    #[test]
+    fn sole_dependents_lose_every_predecessor() {
+        let dag = diamond();
+        assert_eq!(dag.sole_dependents(0).unwrap(), [1, 2, 3, 4]);
+        // 3 still hangs on 0 and 2.
+        assert_eq!(dag.sole_dependents(1).unwrap(), []);
+        assert_eq!(dag.sole_dependents(3).unwrap(), [4]);
+        assert_eq!(dag.sole_dependents(5).unwrap(), []);
+    }
+}
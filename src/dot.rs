@@ -0,0 +1,89 @@
+use std::fmt::Display;
+
+/// Something that can be drawn as a graph, for days whose input is one.
+pub trait GraphExport {
+    fn to_dot(&self) -> DotGraph;
+}
+
+/// Graphviz graph built node by node and edge by edge, its `Display` is
+/// the DOT source.
+#[derive(Debug, Clone)]
+pub struct DotGraph {
+    name: String,
+    directed: bool,
+    attributes: Vec<(String, String)>,
+    statements: Vec<String>,
+}
+
+/// Quoted DOT identifier, so any label is safe to use as a node id.
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn attribute_list(attributes: &[(&str, &str)]) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+    let list = attributes
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, quote(value)))
+        .collect::<Vec<_>>();
+    format!(" [{}]", list.join(", "))
+}
+
+impl DotGraph {
+    pub fn directed(name: &str) -> Self {
+        DotGraph {
+            name: name.to_string(),
+            directed: true,
+            attributes: Vec::new(),
+            statements: Vec::new(),
+        }
+    }
+
+    pub fn undirected(name: &str) -> Self {
+        DotGraph {
+            directed: false,
+            ..DotGraph::directed(name)
+        }
+    }
+
+    /// Graph-wide attribute, e.g. `rankdir` or `layout`.
+    pub fn attribute(&mut self, key: &str, value: &str) -> &mut Self {
+        self.attributes.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn node(&mut self, id: &str, attributes: &[(&str, &str)]) -> &mut Self {
+        self.statements
+            .push(format!("{}{}", quote(id), attribute_list(attributes)));
+        self
+    }
+
+    /// Edge from `from` to `to`, nodes that weren't declared get default looks.
+    pub fn edge(&mut self, from: &str, to: &str, attributes: &[(&str, &str)]) -> &mut Self {
+        let arrow = if self.directed { "->" } else { "--" };
+        self.statements.push(format!(
+            "{} {} {}{}",
+            quote(from),
+            arrow,
+            quote(to),
+            attribute_list(attributes)
+        ));
+        self
+    }
+}
+
+impl Display for DotGraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = if self.directed { "digraph" } else { "graph" };
+        writeln!(f, "{} {} {{", kind, quote(&self.name))?;
+        for (key, value) in &self.attributes {
+            writeln!(f, "    {}={};", key, quote(value))?;
+        }
+        for statement in &self.statements {
+            writeln!(f, "    {};", statement)?;
+        }
+        writeln!(f, "}}")
+    }
+}
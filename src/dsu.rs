@@ -0,0 +1,176 @@
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+
+/// Union-find over the elements `0..len`, with path halving and union by
+/// size, so every operation is close enough to constant time.
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl DisjointSet {
+    /// `len` elements, each in a set of its own.
+    pub fn new(len: usize) -> Self {
+        DisjointSet {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            components: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of disjoint sets.
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// The representative of the set holding `x`.
+    pub fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Merges the sets holding `a` and `b`, returns whether they were apart.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            (a, b) = (b, a);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.components -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the set holding `x`.
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Dense label of every element, sets numbered by their smallest element.
+    pub fn labels(&mut self) -> Vec<usize> {
+        let mut label_of_root = vec![usize::MAX; self.len()];
+        let mut next = 0;
+        (0..self.len())
+            .map(|x| {
+                let root = self.find(x);
+                if label_of_root[root] == usize::MAX {
+                    label_of_root[root] = next;
+                    next += 1;
+                }
+                label_of_root[root]
+            })
+            .collect()
+    }
+
+    /// Elements of every set, sets ordered by their smallest element.
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut components = vec![Vec::new(); self.components];
+        for (x, label) in self.labels().into_iter().enumerate() {
+            components[label].push(x);
+        }
+        components
+    }
+}
+
+/// Connected components of a grid, see [`label_grid`].
+#[derive(Debug, Clone)]
+pub struct Components {
+    /// Component of every cell, numbered row by row in order of first appearance.
+    pub labels: Grid<usize>,
+    /// Number of cells in each component.
+    pub sizes: Vec<usize>,
+}
+
+impl Components {
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+}
+
+/// Labels the cells of `grid` by component, where orthogonal neighbours
+/// share one when `connected(a, value_a, b, value_b)` holds. Cells with no
+/// connections end up alone in a component.
+pub fn label_grid<T>(
+    grid: &Grid<T>,
+    mut connected: impl FnMut(Point, &T, Point, &T) -> bool,
+) -> Components {
+    let width = grid.width();
+    let index = |p: Point| p.y as usize * width + p.x as usize;
+    let mut sets = DisjointSet::new(grid.width() * grid.height());
+    for (p, value) in grid.iter() {
+        // Looking right and down visits every neighbouring pair once.
+        for q in [p + Direction::Right, p + Direction::Down] {
+            if let Some(other) = grid.get(q) {
+                if connected(p, value, q, other) {
+                    sets.union(index(p), index(q));
+                }
+            }
+        }
+    }
+    let labels = sets.labels();
+    let mut sizes = vec![0; sets.component_count()];
+    for &label in &labels {
+        sizes[label] += 1;
+    }
+    Components {
+        labels: Grid::from_vec(width.max(1), labels),
+        sizes,
+    }
+}
+
+/// Dense component label of each of the nodes `0..len` joined by `edges`,
+/// numbered by their smallest node.
+pub fn label_edges(len: usize, edges: impl IntoIterator<Item = (usize, usize)>) -> Vec<usize> {
+    let mut sets = DisjointSet::new(len);
+    for (a, b) in edges {
+        sets.union(a, b);
+    }
+    sets.labels()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_find_and_sizes() {
+        let mut sets = DisjointSet::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(3, 4));
+        assert!(sets.union(1, 4));
+        assert!(!sets.union(0, 3));
+        assert!(sets.same(0, 4));
+        assert!(!sets.same(0, 2));
+        assert_eq!(sets.size_of(3), 4);
+        assert_eq!(sets.size_of(5), 1);
+        assert_eq!(sets.component_count(), 3);
+        assert_eq!(sets.labels(), vec![0, 0, 1, 0, 0, 2]);
+        assert_eq!(sets.components(), vec![vec![0, 1, 3, 4], vec![2], vec![5]]);
+    }
+
+    #[test]
+    fn labels_edges_by_smallest_node() {
+        let labels = label_edges(7, [(5, 6), (1, 3), (6, 2), (3, 4)]);
+        assert_eq!(labels, vec![0, 1, 2, 1, 1, 2, 2]);
+    }}
@@ -0,0 +1,87 @@
+use crate::math::gcd;
+use crate::point::{Direction, Point};
+
+/// Where a point lies relative to a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// Consecutive vertex pairs of a closed polygon, last one back to the first.
+fn edges(polygon: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    polygon
+        .iter()
+        .copied()
+        .zip(polygon.iter().copied().cycle().skip(1))
+}
+
+/// Twice the signed area of `polygon` by the shoelace formula, so it stays an
+/// integer. Positive when the vertices go clockwise on screen, where `y` grows downwards.
+pub fn twice_signed_area(polygon: &[Point]) -> i64 {
+    edges(polygon).map(|(a, b)| a.x * b.y - b.x * a.y).sum()
+}
+
+/// Area of `polygon`, which is a whole number or ends in `.5` on a lattice.
+pub fn area(polygon: &[Point]) -> f64 {
+    twice_signed_area(polygon).abs() as f64 / 2.0
+}
+
+/// Number of lattice points on the edges of `polygon`.
+pub fn boundary_points(polygon: &[Point]) -> i64 {
+    edges(polygon).map(|(a, b)| gcd(b.x - a.x, b.y - a.y)).sum()
+}
+
+/// Number of lattice points strictly inside `polygon`, by Pick's theorem
+/// `A = I + B / 2 - 1`. The polygon must not cross itself.
+pub fn interior_points(polygon: &[Point]) -> i64 {
+    (twice_signed_area(polygon).abs() - boundary_points(polygon) + 2) / 2
+}
+
+/// Number of lattice points inside or on `polygon`, e.g. the cells dug out
+/// when walking the polygon's outline on a grid.
+pub fn enclosed_points(polygon: &[Point]) -> i64 {
+    interior_points(polygon) + boundary_points(polygon)
+}
+
+/// Casts a ray from `p` towards growing `x` and counts the edges it crosses.
+pub fn locate(polygon: &[Point], p: Point) -> Location {
+    let mut inside = false;
+    for (a, b) in edges(polygon) {
+        // The edge crosses row `p.y` at `p.x + cross / (b.y - a.y)`.
+        let cross = (b.x - a.x) * (p.y - a.y) - (p.x - a.x) * (b.y - a.y);
+        let within_x = a.x.min(b.x) <= p.x && p.x <= a.x.max(b.x);
+        let within_y = a.y.min(b.y) <= p.y && p.y <= a.y.max(b.y);
+        if cross == 0 && within_x && within_y {
+            return Location::Boundary;
+        }
+        // Half-open in `y` so a ray through a vertex counts it once.
+        if (a.y > p.y) != (b.y > p.y) && (cross > 0) == (b.y > a.y) {
+            inside = !inside;
+        }
+    }
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+/// Vertices visited walking from `start`, one per step of `(direction, length)`.
+/// A final return to `start` isn't repeated, the polygon closes on its own.
+pub fn polygon_from_walk(
+    start: Point,
+    steps: impl IntoIterator<Item = (Direction, i64)>,
+) -> Vec<Point> {
+    let mut position = start;
+    let mut polygon = vec![start];
+    for (direction, length) in steps {
+        position += direction.offset() * length;
+        polygon.push(position);
+    }
+    if polygon.len() > 1 && polygon.last() == Some(&start) {
+        polygon.pop();
+    }
+    polygon
+}
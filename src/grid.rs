@@ -0,0 +1,216 @@
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use crate::point::{Direction, Point};
+use crate::view::{Orientation, View, ViewMut};
+
+const NEIGHBOURS_8: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// Rectangular grid stored row by row in a single `Vec`.
+/// Positions are [`Point`]s, out of bounds ones are rejected by [`Grid::get`]
+/// and the neighbour iterators, so callers never do the bounds arithmetic.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(width > 0 && cells.len().is_multiple_of(width), "cells don't fill {} columns", width);
+        Grid {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    /// Builds a grid from the lines of `input`, converting each char with `f`.
+    pub fn parse(input: &str, f: impl FnMut(char) -> T) -> Self {
+        Grid::try_parse(input, f).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like [`Grid::parse`], but an empty input or ragged lines are an error.
+    pub fn try_parse(input: &str, mut f: impl FnMut(char) -> T) -> Result<Self, String> {
+        let mut width = 0;
+        let mut cells = Vec::new();
+        for (y, line) in input.lines().enumerate() {
+            let before = cells.len();
+            cells.extend(line.chars().map(&mut f));
+            let line_width = cells.len() - before;
+            if y == 0 {
+                width = line_width;
+            }
+            if line_width != width {
+                return Err(format!(
+                    "line {} has a different width, {} instead of {}",
+                    y + 1,
+                    line_width,
+                    width
+                ));
+            }
+        }
+        if width == 0 {
+            return Err(String::from("the grid is empty"));
+        }
+        Ok(Grid::from_vec(width, cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    fn offset(&self, p: Point) -> Option<usize> {
+        self.contains(p)
+            .then(|| p.y as usize * self.width + p.x as usize)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.offset(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.offset(p).map(|i| &mut self.cells[i])
+    }
+
+    /// In bounds orthogonal neighbours of `p`, clockwise starting from the one above.
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL
+            .into_iter()
+            .map(move |dir| p + dir)
+            .filter(|&n| self.contains(n))
+    }
+
+    /// In bounds neighbours of `p` including diagonals, clockwise from the top left.
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        NEIGHBOURS_8
+            .iter()
+            .map(move |&(dx, dy)| Point::new(p.x + dx, p.y + dy))
+            .filter(|&n| self.contains(n))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Every position, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width as i64, self.height as i64);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Position of the first cell, row by row, matching `predicate`.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter().find(|(_, v)| predicate(v)).map(|(p, _)| p)
+    }
+
+    /// Swaps the values at two positions, panics if either is out of bounds.
+    pub fn swap(&mut self, a: Point, b: Point) {
+        let (i, j) = (self.offset(a), self.offset(b));
+        match (i, j) {
+            (Some(i), Some(j)) => self.cells.swap(i, j),
+            _ => panic!("{:?} or {:?} is outside of a {}x{} grid", a, b, self.width, self.height),
+        }
+    }
+
+    /// The grid as it is, as a starting point for reoriented views.
+    pub fn view(&self) -> View<'_, T> {
+        View::new(self, Orientation::IDENTITY)
+    }
+
+    pub fn view_mut(&mut self) -> ViewMut<'_, T> {
+        ViewMut::new(self, Orientation::IDENTITY)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p)
+            .unwrap_or_else(|| panic!("{:?} is outside of a {}x{} grid", p, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{:?} is outside of a {}x{} grid", p, width, height))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
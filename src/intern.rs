@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+/// Dense id of a string handed out by an [`Interner`], ids count up from 0
+/// in the order strings were first seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Maps labels to [`Symbol`]s and back, so hot loops can compare and index
+/// by `u32` instead of hashing strings.
+#[derive(Debug, Default, Clone)]
+pub struct Interner {
+    ids: HashMap<String, Symbol>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Interner::default()
+    }
+
+    /// The symbol of `name`, a new one if it wasn't seen before.
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(&symbol) = self.ids.get(name) {
+            return symbol;
+        }
+        let symbol = Symbol(u32::try_from(self.names.len()).expect("more than u32::MAX symbols"));
+        self.ids.insert(name.to_string(), symbol);
+        self.names.push(name.to_string());
+        symbol
+    }
+
+    /// The symbol of `name` if it was interned already.
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.ids.get(name).copied()
+    }
+
+    /// The name `symbol` stands for, panics if this interner didn't hand it out.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.names[symbol.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Every symbol with its name, in id order.
+    pub fn symbols(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (Symbol(i as u32), name.as_str()))
+    }
+}
+
+/// Map keyed by [`Symbol`] stored in a `Vec` indexed by id, which is compact
+/// since an interner hands ids out densely.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolMap<V> {
+    values: Vec<Option<V>>,
+}
+
+impl<V> Default for SymbolMap<V> {
+    fn default() -> Self {
+        SymbolMap { values: Vec::new() }
+    }
+}
+
+impl<V> SymbolMap<V> {
+    pub fn new() -> Self {
+        SymbolMap::default()
+    }
+
+    /// Stores `value` for `symbol`, returns the value it replaces.
+    pub fn insert(&mut self, symbol: Symbol, value: V) -> Option<V> {
+        if symbol.index() >= self.values.len() {
+            self.values.resize_with(symbol.index() + 1, || None);
+        }
+        self.values[symbol.index()].replace(value)
+    }
+
+    pub fn get(&self, symbol: Symbol) -> Option<&V> {
+        self.values.get(symbol.index())?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, symbol: Symbol) -> Option<&mut V> {
+        self.values.get_mut(symbol.index())?.as_mut()
+    }
+
+    pub fn contains_key(&self, symbol: Symbol) -> bool {
+        self.get(symbol).is_some()
+    }
+
+    pub fn remove(&mut self, symbol: Symbol) -> Option<V> {
+        self.values.get_mut(symbol.index())?.take()
+    }
+
+    /// Every symbol with a value, in id order.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &V)> {
+        self.values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| v.as_ref().map(|v| (Symbol(i as u32), v)))
+    }
+}
+
+impl<V> FromIterator<(Symbol, V)> for SymbolMap<V> {
+    fn from_iter<I: IntoIterator<Item = (Symbol, V)>>(iter: I) -> Self {
+        let mut map = SymbolMap::new();
+        for (symbol, value) in iter {
+            map.insert(symbol, value);
+        }
+        map
+    }
+}
+
+impl<V> Index<Symbol> for SymbolMap<V> {
+    type Output = V;
+
+    fn index(&self, symbol: Symbol) -> &V {
+        self.get(symbol)
+            .unwrap_or_else(|| panic!("no value for symbol {}", symbol))
+    }
+}
+
+impl<V> IndexMut<Symbol> for SymbolMap<V> {
+    fn index_mut(&mut self, symbol: Symbol) -> &mut V {
+        self.get_mut(symbol)
+            .unwrap_or_else(|| panic!("no value for symbol {}", symbol))
+    }
+}
@@ -0,0 +1,380 @@
+use std::fmt::Display;
+
+/// Half-open interval `start..end`, empty when `end <= start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub const fn new(start: i64, end: i64) -> Self {
+        Interval { start, end }
+    }
+
+    /// `len` values starting at `start`.
+    pub const fn with_len(start: i64, len: i64) -> Self {
+        Interval::new(start, start + len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn len(&self) -> i64 {
+        (self.end - self.start).max(0)
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        self.start <= x && x < self.end
+    }
+
+    pub fn intersect(&self, other: &Interval) -> Interval {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        !self.intersect(other).is_empty()
+    }
+
+    /// The part below `x` and the part from `x` on, either may be empty.
+    pub fn split_at(&self, x: i64) -> (Interval, Interval) {
+        let x = x.clamp(self.start, self.end.max(self.start));
+        (Interval::new(self.start, x), Interval::new(x, self.end))
+    }
+
+    pub fn shift(&self, offset: i64) -> Interval {
+        Interval::new(self.start + offset, self.end + offset)
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// Closed interval `lo..=hi`, how puzzles usually state ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ClosedInterval {
+    pub lo: i64,
+    pub hi: i64,
+}
+
+impl ClosedInterval {
+    pub const fn new(lo: i64, hi: i64) -> Self {
+        ClosedInterval { lo, hi }
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+}
+
+impl From<ClosedInterval> for Interval {
+    fn from(c: ClosedInterval) -> Self {
+        Interval::new(c.lo, c.hi + 1)
+    }
+}
+
+impl TryFrom<Interval> for ClosedInterval {
+    type Error = String;
+
+    fn try_from(i: Interval) -> Result<Self, Self::Error> {
+        if i.is_empty() {
+            Err(format!("{} is empty", i))
+        } else {
+            Ok(ClosedInterval::new(i.start, i.end - 1))
+        }
+    }
+}
+
+/// A set of integers stored as sorted, disjoint and non-touching intervals.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet {
+    intervals: Vec<Interval>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        RangeSet::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of integers in the set.
+    pub fn count(&self) -> i64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.intervals.last().map(|i| i.end - 1)
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        let i = self.intervals.partition_point(|i| i.end <= x);
+        self.intervals.get(i).is_some_and(|i| i.contains(x))
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+        // Everything touching `interval` gets merged into it.
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+        let merged = self.intervals[first..last].iter().fold(interval, |acc, i| {
+            Interval::new(acc.start.min(i.start), acc.end.max(i.end))
+        });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut result = self.clone();
+        for &interval in &other.intervals {
+            result.insert(interval);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let mut result = Vec::new();
+        let (mut a, mut b) = (0, 0);
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (x, y) = (self.intervals[a], other.intervals[b]);
+            let common = x.intersect(&y);
+            if !common.is_empty() {
+                result.push(common);
+            }
+            if x.end < y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        RangeSet { intervals: result }
+    }
+
+    pub fn difference(&self, other: &RangeSet) -> RangeSet {
+        let mut result = Vec::new();
+        let mut b = 0;
+        for &interval in &self.intervals {
+            let mut rest = interval;
+            while b < other.intervals.len() && other.intervals[b].end <= rest.start {
+                b += 1;
+            }
+            let mut c = b;
+            while c < other.intervals.len() && other.intervals[c].start < rest.end {
+                let (before, _) = rest.split_at(other.intervals[c].start);
+                if !before.is_empty() {
+                    result.push(before);
+                }
+                rest = rest.split_at(other.intervals[c].end).1;
+                c += 1;
+            }
+            if !rest.is_empty() {
+                result.push(rest);
+            }
+        }
+        RangeSet { intervals: result }
+    }
+
+    /// The values below `x` and the values from `x` on.
+    pub fn split_at(&self, x: i64) -> (RangeSet, RangeSet) {
+        let (mut below, mut above) = (RangeSet::new(), RangeSet::new());
+        for interval in &self.intervals {
+            let (b, a) = interval.split_at(x);
+            below.insert(b);
+            above.insert(a);
+        }
+        (below, above)
+    }
+}
+
+impl FromIterator<Interval> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+impl From<Interval> for RangeSet {
+    fn from(interval: Interval) -> Self {
+        RangeSet::from_iter([interval])
+    }
+}
+
+/// A function on integers that adds a constant offset on each of a few
+/// disjoint intervals and is the identity everywhere else.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PiecewiseMap {
+    pieces: Vec<(Interval, i64)>,
+}
+
+impl PiecewiseMap {
+    pub fn new() -> Self {
+        PiecewiseMap::default()
+    }
+
+    /// Adds `offset` on `source`, except where an earlier piece already applies.
+    pub fn insert(&mut self, source: Interval, offset: i64) {
+        let covered = self.pieces.iter().map(|&(i, _)| i).collect::<RangeSet>();
+        let fresh = RangeSet::from(source).difference(&covered);
+        self.pieces
+            .extend(fresh.intervals().iter().map(|&i| (i, offset)));
+        self.pieces.sort();
+    }
+
+    pub fn apply(&self, x: i64) -> i64 {
+        let i = self
+            .pieces
+            .partition_point(|(interval, _)| interval.end <= x);
+        match self.pieces.get(i) {
+            Some((interval, offset)) if interval.contains(x) => x + offset,
+            _ => x,
+        }
+    }
+
+    /// Every value `set` is mapped to.
+    pub fn image(&self, set: &RangeSet) -> RangeSet {
+        let mut unmapped = set.clone();
+        let mut result = RangeSet::new();
+        for &(interval, offset) in &self.pieces {
+            let piece = RangeSet::from(interval);
+            for hit in set.intersection(&piece).intervals() {
+                result.insert(hit.shift(offset));
+            }
+            unmapped = unmapped.difference(&piece);
+        }
+        result.union(&unmapped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> RangeSet {
+        intervals
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end))
+            .collect()
+    }
+
+    #[test]
+    fn insert_merges_overlapping_and_adjacent_intervals() {
+        assert_eq!(set(&[(0, 3), (2, 5)]).intervals(), [Interval::new(0, 5)]);
+        assert_eq!(set(&[(0, 3), (3, 5)]).intervals(), [Interval::new(0, 5)]);
+        assert_eq!(
+            set(&[(6, 8), (0, 3), (4, 5)]).intervals(),
+            [
+                Interval::new(0, 3),
+                Interval::new(4, 5),
+                Interval::new(6, 8)
+            ]
+        );
+        assert_eq!(
+            set(&[(0, 2), (4, 6), (1, 5)]).intervals(),
+            [Interval::new(0, 6)]
+        );
+        assert_eq!(set(&[(0, 3), (1, 2)]).intervals(), [Interval::new(0, 3)]);
+    }
+
+    #[test]
+    fn empty_intervals_are_ignored() {
+        assert!(set(&[(3, 3), (5, 2)]).is_empty());
+        assert_eq!(set(&[(0, 2), (2, 2), (4, 1)]), set(&[(0, 2)]));
+        assert_eq!(Interval::new(5, 2).len(), 0);
+        assert_eq!(
+            Interval::new(5, 2).split_at(3),
+            (Interval::new(5, 5), Interval::new(5, 2))
+        );
+    }
+
+    #[test]
+    fn set_queries() {
+        let s = set(&[(0, 3), (5, 7)]);
+        assert_eq!(s.count(), 5);
+        assert_eq!((s.min(), s.max()), (Some(0), Some(6)));
+        assert!(s.contains(0) && s.contains(2) && s.contains(5));
+        assert!(!s.contains(3) && !s.contains(4) && !s.contains(7) && !s.contains(-1));
+        assert_eq!((RangeSet::new().min(), RangeSet::new().max()), (None, None));
+    }
+
+    #[test]
+    fn union_intersection_and_difference() {
+        let a = set(&[(0, 5), (10, 15)]);
+        let b = set(&[(3, 12), (14, 20)]);
+        assert_eq!(a.union(&b), set(&[(0, 20)]));
+        assert_eq!(a.intersection(&b), set(&[(3, 5), (10, 12), (14, 15)]));
+        assert_eq!(a.difference(&b), set(&[(0, 3), (12, 14)]));
+        assert_eq!(b.difference(&a), set(&[(5, 10), (15, 20)]));
+        assert_eq!(a.difference(&a), RangeSet::new());
+        assert_eq!(a.intersection(&RangeSet::new()), RangeSet::new());
+        // Touching but not overlapping intervals share nothing.
+        assert_eq!(
+            set(&[(0, 3)]).intersection(&set(&[(3, 6)])),
+            RangeSet::new()
+        );
+        assert_eq!(set(&[(0, 3)]).difference(&set(&[(3, 6)])), set(&[(0, 3)]));
+    }
+
+    #[test]
+    fn split_at_inside_between_and_outside() {
+        let s = set(&[(0, 5), (10, 15)]);
+        assert_eq!(s.split_at(3), (set(&[(0, 3)]), set(&[(3, 5), (10, 15)])));
+        assert_eq!(s.split_at(7), (set(&[(0, 5)]), set(&[(10, 15)])));
+        assert_eq!(s.split_at(10), (set(&[(0, 5)]), set(&[(10, 15)])));
+        assert_eq!(s.split_at(-1), (RangeSet::new(), s.clone()));
+        assert_eq!(s.split_at(20), (s.clone(), RangeSet::new()));
+    }
+
+    #[test]
+    fn closed_interval_includes_its_upper_bound() {
+        let c = ClosedInterval::new(2, 5);
+        assert!(c.contains(2) && c.contains(5));
+        assert!(!c.contains(1) && !c.contains(6));
+        let i = Interval::from(c);
+        assert_eq!(i, Interval::new(2, 6));
+        assert_eq!(i.len(), 4);
+        assert_eq!(ClosedInterval::try_from(i), Ok(c));
+        assert_eq!(
+            ClosedInterval::try_from(Interval::from(ClosedInterval::new(3, 3))),
+            Ok(ClosedInterval::new(3, 3))
+        );
+        assert!(ClosedInterval::try_from(Interval::new(4, 4)).is_err());
+    }
+
+    #[test]
+    fn piecewise_image_of_a_range_straddling_two_pieces() {
+        let mut map = PiecewiseMap::new();
+        map.insert(Interval::new(10, 20), 100);
+        map.insert(Interval::new(20, 30), -15);
+        // Earlier pieces win where they overlap later ones.
+        map.insert(Interval::new(15, 25), 1000);
+        assert_eq!(map.apply(9), 9);
+        assert_eq!(map.apply(15), 115);
+        assert_eq!(map.apply(20), 5);
+        assert_eq!(map.apply(30), 30);
+
+        let image = map.image(&set(&[(5, 25)]));
+        // 20..25 lands on 5..10, which the identity part already covers.
+        assert_eq!(image, set(&[(5, 10), (110, 120)]));
+        let image = map.image(&set(&[(18, 22)]));
+        assert_eq!(image, set(&[(5, 7), (118, 120)]));
+        assert_eq!(image.count(), 4);
+    }
+}
@@ -0,0 +1,25 @@
+pub mod bignum;
+pub mod bitgrid;
+pub mod counters;
+pub mod cycle;
+pub mod dag;
+pub mod dot;
+pub mod dsu;
+pub mod geometry;
+pub mod grid;
+pub mod intern;
+pub mod interval;
+pub mod linalg;
+pub mod math;
+pub mod memo;
+pub mod parallel;
+pub mod parse;
+pub mod point;
+pub mod registry;
+pub mod search;
+pub mod sim;
+pub mod solutions;
+pub mod trace;
+pub mod validate;
+pub mod view;
+pub mod zobrist;
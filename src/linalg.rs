@@ -0,0 +1,443 @@
+use std::fmt::Display;
+use std::ops::{Index, IndexMut, Mul};
+
+use crate::bignum::Rational;
+
+pub type Vector = Vec<Rational>;
+
+/// Vector of exact values from anything that converts into them.
+pub fn vector<T: Into<Rational>>(values: impl IntoIterator<Item = T>) -> Vector {
+    values.into_iter().map(Into::into).collect()
+}
+
+pub fn dot(a: &[Rational], b: &[Rational]) -> Rational {
+    assert_eq!(a.len(), b.len(), "dot product of different lengths");
+    a.iter()
+        .zip(b)
+        .fold(Rational::zero(), |acc, (x, y)| acc + x * y)
+}
+
+/// Dense matrix of exact rationals, stored row by row. Meant for the handful
+/// of unknowns puzzles have, where floating point rounding can't be trusted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    cells: Vec<Rational>,
+}
+
+/// Result of [`Matrix::row_reduce`].
+#[derive(Debug, Clone)]
+pub struct Echelon {
+    /// The reduced row echelon form, every pivot is 1 and alone in its column.
+    pub matrix: Matrix,
+    /// Column of each row's pivot, for the first `rank` rows.
+    pub pivots: Vec<usize>,
+    /// Product of the factors rows were divided by, with a sign flip per swap.
+    /// For a square matrix this is its determinant.
+    pub scale: Rational,
+}
+
+impl Matrix {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Matrix::from_fn(rows, cols, |_, _| Rational::zero())
+    }
+
+    pub fn identity(n: usize) -> Self {
+        Matrix::from_fn(n, n, |r, c| Rational::from(i64::from(r == c)))
+    }
+
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> Rational) -> Self {
+        let cells = (0..rows * cols).map(|i| f(i / cols, i % cols)).collect();
+        Matrix { rows, cols, cells }
+    }
+
+    /// Panics if the rows don't all have the same length.
+    pub fn from_rows<T: Into<Rational>>(rows: Vec<Vec<T>>) -> Self {
+        let cols = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(height * cols);
+        for (r, row) in rows.into_iter().enumerate() {
+            assert_eq!(row.len(), cols, "row {} has a different length", r);
+            cells.extend(row.into_iter().map(Into::into));
+        }
+        Matrix {
+            rows: height,
+            cols,
+            cells,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, r: usize) -> &[Rational] {
+        &self.cells[r * self.cols..(r + 1) * self.cols]
+    }
+
+    pub fn column(&self, c: usize) -> Vector {
+        (0..self.rows).map(|r| self[(r, c)].clone()).collect()
+    }
+
+    pub fn transpose(&self) -> Matrix {
+        Matrix::from_fn(self.cols, self.rows, |r, c| self[(c, r)].clone())
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for c in 0..self.cols {
+            self.cells.swap(a * self.cols + c, b * self.cols + c);
+        }
+    }
+
+    /// This matrix with `other`'s columns appended.
+    pub fn augment(&self, other: &Matrix) -> Matrix {
+        assert_eq!(self.rows, other.rows, "augmenting with a different height");
+        Matrix::from_fn(self.rows, self.cols + other.cols, |r, c| {
+            if c < self.cols {
+                self[(r, c)].clone()
+            } else {
+                other[(r, c - self.cols)].clone()
+            }
+        })
+    }
+
+    /// Gauss-Jordan elimination.
+    pub fn row_reduce(&self) -> Echelon {
+        let mut m = self.clone();
+        let mut pivots = Vec::new();
+        let mut scale = Rational::from(1);
+        for c in 0..m.cols {
+            let r = pivots.len();
+            let Some(pivot_row) = (r..m.rows).find(|&i| !m[(i, c)].is_zero()) else {
+                continue;
+            };
+            if pivot_row != r {
+                m.swap_rows(pivot_row, r);
+                scale = -scale;
+            }
+            let pivot = m[(r, c)].clone();
+            scale *= &pivot;
+            for k in 0..m.cols {
+                m[(r, k)] = &m[(r, k)] / &pivot;
+            }
+            for i in (0..m.rows).filter(|&i| i != r) {
+                let factor = m[(i, c)].clone();
+                if factor.is_zero() {
+                    continue;
+                }
+                for k in 0..m.cols {
+                    m[(i, k)] = &m[(i, k)] - &(&factor * &m[(r, k)]);
+                }
+            }
+            pivots.push(c);
+            if pivots.len() == m.rows {
+                break;
+            }
+        }
+        Echelon {
+            matrix: m,
+            pivots,
+            scale,
+        }
+    }
+
+    pub fn rank(&self) -> usize {
+        self.row_reduce().pivots.len()
+    }
+
+    /// Panics if the matrix isn't square.
+    pub fn determinant(&self) -> Rational {
+        assert_eq!(self.rows, self.cols, "determinant of a non-square matrix");
+        let echelon = self.row_reduce();
+        if echelon.pivots.len() < self.rows {
+            Rational::zero()
+        } else {
+            echelon.scale
+        }
+    }
+
+    /// `None` if the matrix is singular, panics if it isn't square.
+    pub fn inverse(&self) -> Option<Matrix> {
+        assert_eq!(self.rows, self.cols, "inverse of a non-square matrix");
+        let echelon = self.augment(&Matrix::identity(self.rows)).row_reduce();
+        if echelon.pivots.iter().copied().ne(0..self.rows) {
+            return None;
+        }
+        let n = self.rows;
+        Some(Matrix::from_fn(n, n, |r, c| {
+            echelon.matrix[(r, n + c)].clone()
+        }))
+    }
+
+    /// The `x` with `self * x == b`, or why there isn't exactly one.
+    pub fn solve(&self, b: &[Rational]) -> Result<Vector, String> {
+        assert_eq!(b.len(), self.rows, "right-hand side of the wrong length");
+        let rhs = Matrix::from_fn(self.rows, 1, |r, _| b[r].clone());
+        let echelon = self.augment(&rhs).row_reduce();
+        if echelon.pivots.contains(&self.cols) {
+            return Err(String::from("the system has no solution"));
+        }
+        if echelon.pivots.len() < self.cols {
+            return Err(format!(
+                "the system has infinitely many solutions, rank {} for {} unknowns",
+                echelon.pivots.len(),
+                self.cols
+            ));
+        }
+        Ok((0..self.cols)
+            .map(|r| echelon.matrix[(r, self.cols)].clone())
+            .collect())
+    }
+
+    /// The `x` minimising `|self * x - b|`, through the normal equations.
+    /// Fails if the columns aren't independent.
+    pub fn least_squares(&self, b: &[Rational]) -> Result<Vector, String> {
+        let transposed = self.transpose();
+        (&transposed * self).solve(&(&transposed * b))
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = Rational;
+
+    fn index(&self, (r, c): (usize, usize)) -> &Rational {
+        assert!(
+            r < self.rows && c < self.cols,
+            "({}, {}) is outside of a {}x{} matrix",
+            r,
+            c,
+            self.rows,
+            self.cols
+        );
+        &self.cells[r * self.cols + c]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut Rational {
+        assert!(
+            r < self.rows && c < self.cols,
+            "({}, {}) is outside of a {}x{} matrix",
+            r,
+            c,
+            self.rows,
+            self.cols
+        );
+        &mut self.cells[r * self.cols + c]
+    }
+}
+
+impl Mul for &Matrix {
+    type Output = Matrix;
+
+    fn mul(self, other: &Matrix) -> Matrix {
+        assert_eq!(
+            self.cols, other.rows,
+            "multiplying a {}x{} by a {}x{} matrix",
+            self.rows, self.cols, other.rows, other.cols
+        );
+        Matrix::from_fn(self.rows, other.cols, |r, c| {
+            dot(self.row(r), &other.column(c))
+        })
+    }
+}
+
+impl Mul<&[Rational]> for &Matrix {
+    type Output = Vector;
+
+    fn mul(self, v: &[Rational]) -> Vector {
+        (0..self.rows).map(|r| dot(self.row(r), v)).collect()
+    }
+}
+
+impl Display for Matrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for r in 0..self.rows {
+            let row = self
+                .row(r)
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>();
+            writeln!(f, "[{}]", row.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Coefficients, lowest degree first, of the polynomial of degree at most
+/// `degree` that fits `points` best, exactly through them if it can.
+pub fn fit_polynomial(points: &[(Rational, Rational)], degree: usize) -> Result<Vector, String> {
+    let vandermonde = Matrix::from_fn(points.len(), degree + 1, |r, c| {
+        let mut power = Rational::from(1);
+        for _ in 0..c {
+            power *= &points[r].0;
+        }
+        power
+    });
+    let ys = points.iter().map(|(_, y)| y.clone()).collect::<Vector>();
+    vandermonde.least_squares(&ys)
+}
+
+/// Value at `x` of the polynomial with `coefficients`, lowest degree first.
+pub fn eval_polynomial(coefficients: &[Rational], x: &Rational) -> Rational {
+    coefficients
+        .iter()
+        .rev()
+        .fold(Rational::zero(), |acc, c| acc * x + c)
+}
+
+/// Parameters `(t, s)` where the 2D lines `p + t * d` and `q + s * e` meet,
+/// `None` if they are parallel.
+pub fn intersect_lines(
+    p: &[Rational],
+    d: &[Rational],
+    q: &[Rational],
+    e: &[Rational],
+) -> Option<(Rational, Rational)> {
+    assert!(
+        [p, d, q, e].iter().all(|v| v.len() == 2),
+        "lines must be in 2D"
+    );
+    // p + t * d = q + s * e  <=>  t * d - s * e = q - p
+    let a = Matrix::from_fn(2, 2, |r, c| if c == 0 { d[r].clone() } else { -&e[r] });
+    let b = vector([&q[0] - &p[0], &q[1] - &p[1]]);
+    let ts = a.solve(&b).ok()?;
+    Some((ts[0].clone(), ts[1].clone()))
+}
+
+/// Parameter `t` where the line `p + t * d` crosses the plane through
+/// `origin` with `normal`, `None` if they are parallel.
+pub fn intersect_line_plane(
+    p: &[Rational],
+    d: &[Rational],
+    origin: &[Rational],
+    normal: &[Rational],
+) -> Option<Rational> {
+    let along = dot(normal, d);
+    if along.is_zero() {
+        return None;
+    }
+    let offset = origin.iter().zip(p).map(|(o, p)| o - p).collect::<Vector>();
+    Some(&dot(normal, &offset) / &along)
+}
+
+/// Cross product of two 3D vectors.
+pub fn cross(a: &[Rational], b: &[Rational]) -> Vector {
+    assert!(
+        a.len() == 3 && b.len() == 3,
+        "cross product needs 3D vectors"
+    );
+    vec![
+        &a[1] * &b[2] - &a[2] * &b[1],
+        &a[2] * &b[0] - &a[0] * &b[2],
+        &a[0] * &b[1] - &a[1] * &b[0],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bignum::BigInt;
+
+    fn ratio(numer: i64, denom: i64) -> Rational {
+        Rational::new(BigInt::from(numer), BigInt::from(denom))
+    }
+
+    #[test]
+    fn singular_matrix_has_no_inverse_or_unique_solution() {
+        let m = Matrix::from_rows(vec![vec![1, 2], vec![2, 4]]);
+        assert_eq!(m.rank(), 1);
+        assert_eq!(m.determinant(), Rational::zero());
+        assert_eq!(m.inverse(), None);
+        let err = m.solve(&vector([3, 6])).unwrap_err();
+        assert!(err.contains("infinitely many"), "{}", err);
+        let err = m.solve(&vector([3, 7])).unwrap_err();
+        assert!(err.contains("no solution"), "{}", err);
+    }
+
+    #[test]
+    fn underdetermined_system_has_free_unknowns() {
+        let m = Matrix::from_rows(vec![vec![1, 1, 1], vec![0, 1, 2]]);
+        let echelon = m.row_reduce();
+        assert_eq!(echelon.pivots, vec![0, 1]);
+        assert_eq!(
+            echelon.matrix,
+            Matrix::from_rows(vec![vec![1, 0, -1], vec![0, 1, 2]])
+        );
+        let err = m.solve(&vector([6, 5])).unwrap_err();
+        assert!(err.contains("rank 2 for 3 unknowns"), "{}", err);
+    }
+
+    #[test]
+    fn determinant_inverse_and_solve() {
+        let m = Matrix::from_rows(vec![vec![2, -1, 0], vec![1, 3, 2], vec![0, 1, 4]]);
+        assert_eq!(m.determinant(), Rational::from(24));
+        let inverse = m.inverse().unwrap();
+        assert_eq!(&m * &inverse, Matrix::identity(3));
+        let x = vector([1, -2, 3]);
+        assert_eq!(m.solve(&(&m * x.as_slice())), Ok(x));
+        // The first pivot needs a row swap, which flips the sign.
+        let swap = Matrix::from_rows(vec![vec![0, 1], vec![1, 0]]);
+        assert_eq!(swap.determinant(), Rational::from(-1));
+    }
+
+    #[test]
+    fn least_squares_fits_overdetermined_systems() {
+        let points = |ys: [i64; 4]| {
+            (0..4)
+                .map(|x| (Rational::from(x), Rational::from(ys[x as usize])))
+                .collect::<Vec<_>>()
+        };
+        // Exactly on the line y = 1 + 2x.
+        assert_eq!(fit_polynomial(&points([1, 3, 5, 7]), 1), Ok(vector([1, 2])));
+        // Not on any line, the best fit is y = 1/10 + 3/5 x.
+        assert_eq!(
+            fit_polynomial(&points([0, 1, 1, 2]), 1),
+            Ok(vec![ratio(1, 10), ratio(3, 5)])
+        );
+        let square = fit_polynomial(&points([0, 1, 4, 9]), 2).unwrap();
+        assert_eq!(
+            eval_polynomial(&square, &Rational::from(5)),
+            Rational::from(25)
+        );
+    }
+
+    #[test]
+    fn intersects_lines_unless_parallel() {
+        let parallel = intersect_lines(
+            &vector([0, 0]),
+            &vector([1, 1]),
+            &vector([1, 0]),
+            &vector([2, 2]),
+        );
+        assert_eq!(parallel, None);
+        let crossing = intersect_lines(
+            &vector([0, 0]),
+            &vector([1, 0]),
+            &vector([2, -1]),
+            &vector([0, 1]),
+        );
+        assert_eq!(crossing, Some((Rational::from(2), Rational::from(1))));
+    }
+
+    #[test]
+    fn intersects_line_and_plane() {
+        let origin = vector([0, 0, 3]);
+        let normal = cross(&vector([1, 0, 0]), &vector([0, 1, 0]));
+        assert_eq!(normal, vector([0, 0, 1]));
+        let p = vector([0, 0, 0]);
+        assert_eq!(
+            intersect_line_plane(&p, &vector([1, 1, 2]), &origin, &normal),
+            Some(ratio(3, 2))
+        );
+        assert_eq!(
+            intersect_line_plane(&p, &vector([1, 0, 0]), &origin, &normal),
+            None
+        );
+    }
+}
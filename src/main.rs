@@ -1,71 +1,211 @@
-mod solutions;
-use solutions::*;
-
-type AOCSolutions = (fn(&String) -> String, fn(&String) -> String);
-
-static SOLUTION_FUNCS: [AOCSolutions; 25] = [
-    (day01::p1, day01::p2),
-    (day02::p1, day02::p2),
-    (day03::p1, day03::p2),
-    (day04::p1, day04::p2),
-    (day05::p1, day05::p2),
-    (day06::p1, day06::p2),
-    (day07::p1, day07::p2),
-    (day08::p1, day08::p2),
-    (day09::p1, day09::p2),
-    (day10::p1, day10::p2),
-    (day11::p1, day11::p2),
-    (day12::p1, day12::p2),
-    (day13::p1, day13::p2),
-    (day14::p1, day14::p2),
-    (day15::p1, day15::p2),
-    (day16::p1, day16::p2),
-    (day17::p1, day17::p2),
-    (day18::p1, day18::p2),
-    (day19::p1, day19::p2),
-    (day20::p1, day20::p2),
-    (day21::p1, day21::p2),
-    (day22::p1, day22::p2),
-    (day23::p1, day23::p2),
-    (day24::p1, day24::p2),
-    (day25::p1, day25::p2),
-];
+mod alloc;
+mod bench;
+mod report;
+
+use itertools::Itertools;
+
+use aoc2023::{counters, parallel, sim, trace};
+use aoc2023::registry::{SolutionFn, DAYS};
+
+#[global_allocator]
+static GLOBAL: alloc::CountingAlloc = alloc::CountingAlloc;
+
+#[derive(Default)]
+struct Options {
+    /// `--mem`: report allocation count, bytes allocated and peak live bytes per run.
+    mem: bool,
+    /// `--trace <path>`: write the recorded spans as a Chrome trace to `path`.
+    trace: Option<String>,
+    /// `--dot <path>`: write the input as a Graphviz graph to `path`, for days that export one.
+    dot: Option<String>,
+    /// `--threads <n>`: spread parallel parts over `n` threads instead of one per core.
+    threads: Option<usize>,
+    /// `--frames <every>`: print the state every `every` steps of days that simulate one.
+    frames: Option<usize>,
+    /// `--debug-steps`: log every step of days that simulate one to stderr.
+    debug_steps: bool,
+}
+
+struct Run {
+    part: usize,
+    answer: String,
+    alloc: Option<alloc::Stats>,
+    counters: Vec<(String, u64)>,
+    frames: Vec<(usize, String)>,
+}
+
+/// Splits the command line into positional arguments and `--` options.
+fn parse_args() -> (Vec<String>, Options) {
+    let mut args = Vec::new();
+    let mut options = Options::default();
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        match arg.as_str() {
+            "--mem" => options.mem = true,
+            "--trace" => options.trace = Some(argv.next().expect("--trace needs an output path")),
+            "--dot" => options.dot = Some(argv.next().expect("--dot needs an output path")),
+            "--threads" => {
+                let n = argv.next().expect("--threads needs a thread count");
+                let n = n
+                    .parse::<usize>()
+                    .unwrap_or_else(|_| panic!("threads = {} is not a valid value", n));
+                assert!(n > 0, "threads must be at least 1");
+                options.threads = Some(n);
+            }
+            "--frames" => {
+                let every = argv.next().expect("--frames needs a step interval");
+                let every = every
+                    .parse::<usize>()
+                    .unwrap_or_else(|_| panic!("frames = {} is not a valid value", every));
+                assert!(every > 0, "frames must be at least 1");
+                options.frames = Some(every);
+            }
+            "--debug-steps" => options.debug_steps = true,
+            flag if flag.starts_with("--") => panic!("unknown option {}", flag),
+            _ => args.push(arg),
+        }
+    }
+    (args, options)
+}
 
 fn read_file(input_filepath: &String) -> String {
-    std::fs::read_to_string(input_filepath).expect(
-        format!("{} no such file was found", input_filepath).as_str()
-    )
+    std::fs::read_to_string(input_filepath)
+        .unwrap_or_else(|_| panic!("{} no such file was found", input_filepath))
+}
+
+fn parse_problem_number(value: &str) -> usize {
+    let problem_number = value
+        .parse::<usize>()
+        .unwrap_or_else(|_| panic!("problem_number = {} is not a valid value", value));
+    assert!((1..=25).contains(&problem_number), "problem number must be within 1..=25 got = {}", problem_number);
+    problem_number
+}
+
+fn solution_for(problem_number: usize, part: usize) -> SolutionFn {
+    DAYS[problem_number - 1].parts[part - 1].unwrap_or_else(|| {
+        panic!("day {} part {} is not implemented yet", problem_number, part)
+    })
+}
+
+/// Checks the input against every assumption the day declares, returns whether all hold.
+fn validate(problem_number: usize, input: &String) -> bool {
+    let assumptions = DAYS[problem_number - 1].assumptions;
+    if assumptions.is_empty() {
+        println!("day {} declares no input assumptions", problem_number);
+    }
+    let mut all_hold = true;
+    for assumption in assumptions {
+        match (assumption.check)(input) {
+            Ok(()) => println!("ok   {}", assumption.description),
+            Err(reason) => {
+                println!("FAIL {}: {}", assumption.description, reason);
+                all_hold = false;
+            }
+        }
+    }
+    all_hold
+}
+
+fn run_part(problem_number: usize, part: usize, input: &String, options: &Options) -> Run {
+    let solution = solution_for(problem_number, part);
+    counters::take();
+    sim::take_frames();
+    if options.mem {
+        alloc::start();
+    }
+    let answer = {
+        let _span = trace::span(format!("day{:02} p{}", problem_number, part));
+        solution(input)
+    };
+    let alloc = options.mem.then(alloc::stop);
+    Run {
+        part,
+        answer,
+        alloc,
+        counters: counters::take(),
+        frames: sim::take_frames(),
+    }
 }
 
 fn main() {
-    let problem_number = std::env::args().nth(1).expect("no problem number was passed");
-    let problem_number = problem_number.parse::<usize>().expect(
-        format!("problem_number = {} is not a valid value", problem_number).as_str()
-    );
-    assert!(problem_number < 25, "problem number must be within 1..=25 got = {}", problem_number);
-    let input_filepath = std::env::args().nth(2).expect("no problem input file was given");
-
-    let only_part = std::env::args().nth(3).unwrap_or(
-        String::from("0")
-    ).parse::<usize>().unwrap_or(0);
+    let (args, options) = parse_args();
+    if let Some(n) = options.threads {
+        parallel::set_threads(n);
+    }
+    if let Some(every) = options.frames {
+        sim::capture_frames(every);
+    }
+    if options.debug_steps {
+        sim::log_steps();
+    }
+    let command = args.first().expect("no problem number was passed");
+    match command.as_str() {
+        "bench" => {
+            let runs = args.get(1).map_or(10, |v| {
+                v.parse::<usize>()
+                    .unwrap_or_else(|_| panic!("runs = {} is not a valid value", v))
+            });
+            bench::run(runs);
+            return;
+        }
+        "report" => {
+            let path = args.get(1).map_or(report::REPORT_PATH, |v| v.as_str());
+            report::write(path);
+            return;
+        }
+        "validate" => {
+            let problem_number = parse_problem_number(args.get(1).expect("no problem number was passed"));
+            let input = read_file(args.get(2).expect("no problem input file was given"));
+            if !validate(problem_number, &input) {
+                std::process::exit(1);
+            }
+            return;
+        }
+        _ => (),
+    }
+
+    let problem_number = parse_problem_number(command);
+    let input_filepath = args.get(1).expect("no problem input file was given");
+
+    let only_part = args.get(2).map_or(0, |v| v.parse::<usize>().unwrap_or(0));
     assert!(only_part < 3, "part number cannot be > 2");
 
-    let input = read_file(&input_filepath);
-
-    match only_part {
-        0 => {
-            let s1 = SOLUTION_FUNCS[problem_number-1].0(&input);
-            let s2 = SOLUTION_FUNCS[problem_number-1].1(&input);
-            println!("p1 = {}, p2 = {}", s1, s2);
-        },
-        1 => {
-            let s1 = SOLUTION_FUNCS[problem_number-1].0(&input);
-            println!("p1 = {}", s1);
-        },
-        2 => {
-            let s2 = SOLUTION_FUNCS[problem_number-1].1(&input);
-            println!("p2 = {}", s2);
+    let input = read_file(input_filepath);
+    if let Some(path) = &options.dot {
+        let graph = DAYS[problem_number - 1]
+            .graph
+            .unwrap_or_else(|| panic!("day {} has no graph export", problem_number));
+        std::fs::write(path, graph(&input)).unwrap_or_else(|e| panic!("couldn't write {}: {}", path, e));
+    }
+    if options.trace.is_some() {
+        trace::enable();
+    }
+
+    let parts = match only_part {
+        0 => vec![1, 2],
+        part => vec![part],
+    };
+    let runs = parts
+        .into_iter()
+        .map(|part| run_part(problem_number, part, &input, &options))
+        .collect_vec();
+
+    println!("{}", runs.iter().map(|run| format!("p{} = {}", run.part, run.answer)).join(", "));
+    for run in &runs {
+        if let Some(stats) = run.alloc {
+            println!(
+                "p{} allocations = {}, allocated = {} B, peak = {} B",
+                run.part, stats.allocations, stats.allocated, stats.peak
+            );
+        }
+        for (name, value) in &run.counters {
+            println!("p{} {} = {}", run.part, name, value);
         }
-        _ => unreachable!()
+        for (step, frame) in &run.frames {
+            println!("p{} step {}:\n{}", run.part, step, frame);
+        }
+    }
+    if let Some(path) = &options.trace {
+        trace::write_chrome_trace(path).unwrap_or_else(|e| panic!("couldn't write {}: {}", path, e));
     }
 }
@@ -0,0 +1,202 @@
+use std::ops::{Div, Mul, Rem, Sub};
+
+/// The primitive integers the helpers below work on.
+pub trait Integer:
+    Copy
+    + PartialOrd
+    + Rem<Output = Self>
+    + Div<Output = Self>
+    + Mul<Output = Self>
+    + Sub<Output = Self>
+{
+    const ZERO: Self;
+
+    fn magnitude(self) -> Self;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! unsigned {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+
+            fn magnitude(self) -> Self {
+                self
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+        }
+    )*};
+}
+
+macro_rules! signed {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+
+            fn magnitude(self) -> Self {
+                self.abs()
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+        }
+    )*};
+}
+
+unsigned!(u8, u16, u32, u64, u128, usize);
+signed!(i8, i16, i32, i64, i128, isize);
+
+/// Greatest common divisor, never negative. `gcd(0, 0)` is 0.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a.magnitude(), b.magnitude());
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, panics (in debug builds) if it overflows `T`.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    (a / gcd(a, b) * b).magnitude()
+}
+
+/// Least common multiple, `None` if it doesn't fit in `T`.
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / gcd(a, b)).checked_mul(b).map(Integer::magnitude)
+}
+
+/// Least common multiple of every value, `None` if it is empty or overflows.
+pub fn checked_lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    let mut values = values.into_iter();
+    let first = values.next()?;
+    values.try_fold(first, checked_lcm)
+}
+
+/// Least common multiple of every value computed in `u128`, for when the
+/// result may not fit the type the values come in.
+pub fn lcm_u128<T: Into<u128>>(values: impl IntoIterator<Item = T>) -> Option<u128> {
+    checked_lcm_all(values.into_iter().map(Into::into))
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Solves `x ≡ residue (mod modulus)` for every pair at once, moduli don't
+/// need to be coprime. Returns the smallest non-negative solution and the
+/// period all solutions repeat with, or which congruence contradicts the
+/// ones before it.
+pub fn crt(congruences: &[(i128, i128)]) -> Result<(i128, i128), String> {
+    let mut solution: (i128, i128) = (0, 1);
+    for &(residue, modulus) in congruences {
+        assert!(modulus > 0, "modulus must be positive, got {}", modulus);
+        let (r1, m1) = solution;
+        let r2 = residue.rem_euclid(modulus);
+        let (g, p, _) = extended_gcd(m1, modulus);
+        if (r2 - r1) % g != 0 {
+            return Err(format!(
+                "x ≡ {} (mod {}) contradicts x ≡ {} (mod {})",
+                residue, modulus, r1, m1
+            ));
+        }
+        let m = m1 / g * modulus;
+        // r1 + m1 * k hits r2 when k ≡ (r2 - r1) / g * p (mod modulus / g)
+        let k = ((r2 - r1) / g * p).rem_euclid(modulus / g);
+        solution = ((r1 + m1 * k).rem_euclid(m), m);
+    }
+    Ok(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm_ignore_signs() {
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(-12i32, 18), 6);
+        assert_eq!(lcm(-4i32, 6), 12);
+        assert_eq!(lcm(0u32, 6), 0);
+    }
+
+    #[test]
+    fn checked_lcm_reports_overflow() {
+        assert_eq!(checked_lcm(4u8, 6), Some(12));
+        assert_eq!(checked_lcm(200u8, 3), None);
+        assert_eq!(checked_lcm(0u8, 200), Some(0));
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(checked_lcm_all([2u64, 3, 4]), Some(12));
+        assert_eq!(checked_lcm_all(Vec::<u64>::new()), None);
+        assert_eq!(
+            lcm_u128([u64::MAX, u64::MAX - 1]),
+            Some(u64::MAX as u128 * (u64::MAX - 1) as u128)
+        );
+    }
+
+    #[test]
+    fn extended_gcd_gives_bezout_coefficients() {
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        for (a, b) in [(-12, 18), (18, -12), (-7, -21), (0, 5), (5, 0)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn mod_inverse_only_for_coprime_values() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(0, 7), None);
+    }
+
+    #[test]
+    fn crt_with_coprime_and_shared_moduli() {
+        assert_eq!(crt(&[]), Ok((0, 1)));
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Ok((23, 105)));
+        // 4 and 6 share a factor of 2, the residues agree on it.
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Ok((10, 12)));
+        assert_eq!(crt(&[(-1, 5), (-2, 7)]), Ok((19, 35)));
+        // 8 is a multiple of 4, so the second congruence refines the first.
+        assert_eq!(crt(&[(7, 4), (3, 8)]), Ok((3, 8)));
+    }
+
+    #[test]
+    fn crt_rejects_contradictions() {
+        let err = crt(&[(1, 4), (2, 6)]).unwrap_err();
+        assert_eq!(err, "x ≡ 2 (mod 6) contradicts x ≡ 1 (mod 4)");
+        let err = crt(&[(-1, 4), (2, 6)]).unwrap_err();
+        assert_eq!(err, "x ≡ 2 (mod 6) contradicts x ≡ 3 (mod 4)");
+    }
+}
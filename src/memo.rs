@@ -0,0 +1,123 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// What a capped [`Memo`] throws away once it holds `capacity` entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Eviction {
+    /// Forget everything and start over, cheap and fine for phase-like workloads.
+    Clear,
+    /// Forget the entry that was inserted first.
+    Oldest,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    /// Most entries held at once.
+    pub peak: usize,
+}
+
+/// Cache for a recursive function, see [`Memo::recurse`].
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    order: VecDeque<K>,
+    limit: Option<(usize, Eviction)>,
+    stats: Stats,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo {
+            cache: HashMap::new(),
+            order: VecDeque::new(),
+            limit: None,
+            stats: Stats::default(),
+        }
+    }
+
+    /// A memo that never holds more than `capacity` entries.
+    pub fn capped(capacity: usize, eviction: Eviction) -> Self {
+        assert!(capacity > 0, "a memo needs room for at least one entry");
+        Memo {
+            limit: Some((capacity, eviction)),
+            ..Self::new()
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let value = self.cache.get(key).cloned();
+        match value {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        value
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if let Some((capacity, eviction)) = self.limit {
+            if self.cache.len() >= capacity && !self.cache.contains_key(&key) {
+                match eviction {
+                    Eviction::Clear => {
+                        self.stats.evictions += self.cache.len() as u64;
+                        self.cache.clear();
+                        self.order.clear();
+                    }
+                    Eviction::Oldest => {
+                        if let Some(oldest) = self.order.pop_front() {
+                            self.cache.remove(&oldest);
+                            self.stats.evictions += 1;
+                        }
+                    }
+                }
+            }
+            if eviction == Eviction::Oldest && !self.cache.contains_key(&key) {
+                self.order.push_back(key.clone());
+            }
+        }
+        self.cache.insert(key, value);
+        self.stats.peak = self.stats.peak.max(self.cache.len());
+    }
+
+    /// Evaluates `body(recurse, arg)` once per distinct `key(&arg)`. `body`
+    /// calls `recurse` instead of itself for its subproblems, so only the
+    /// key has to be hashed and the rest of the state can live in captures.
+    pub fn recurse<A>(
+        &mut self,
+        arg: A,
+        key: &impl Fn(&A) -> K,
+        body: &impl Fn(&mut dyn FnMut(A) -> V, A) -> V,
+    ) -> V {
+        let k = key(&arg);
+        if let Some(value) = self.get(&k) {
+            return value;
+        }
+        let value = body(&mut |next| self.recurse(next, key, body), arg);
+        self.insert(k, value.clone());
+        value
+    }
+
+    /// [`Memo::recurse`] for when the argument is its own key.
+    pub fn call(&mut self, arg: K, body: &impl Fn(&mut dyn FnMut(K) -> V, K) -> V) -> V {
+        self.recurse(arg, &K::clone, body)
+    }
+}
@@ -0,0 +1,83 @@
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+use std::thread;
+
+use crate::trace;
+
+/// Thread count set by [`set_threads`], 0 meaning one per available core.
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Items a worker claims at a time, small enough that a slow item doesn't
+/// leave the other workers idle for long.
+const CHUNK: usize = 4;
+
+/// Uses `n` threads for parallel work from now on, 0 goes back to one per core.
+pub fn set_threads(n: usize) {
+    THREADS.store(n, Relaxed);
+}
+
+/// Number of threads parallel work is spread over.
+pub fn threads() -> usize {
+    match THREADS.load(Relaxed) {
+        0 => thread::available_parallelism().map_or(1, usize::from),
+        n => n,
+    }
+}
+
+/// `f` applied to every item, results in the same order as `items`. Workers
+/// take the next few unclaimed items whenever they are done with theirs, so
+/// uneven items still keep every thread busy. A panic in `f` is re-raised
+/// here once all the workers have stopped.
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = threads().min(items.len().div_ceil(CHUNK));
+    if workers <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let work = || {
+        let _span = trace::span("parallel worker");
+        let mut done = Vec::new();
+        loop {
+            let start = next.fetch_add(CHUNK, Relaxed);
+            if start >= items.len() {
+                return done;
+            }
+            let end = (start + CHUNK).min(items.len());
+            done.extend((start..end).map(|i| (i, f(&items[i]))));
+        }
+    };
+
+    let mut results = items.iter().map(|_| None).collect::<Vec<_>>();
+    thread::scope(|scope| {
+        let handles = (0..workers).map(|_| scope.spawn(work)).collect::<Vec<_>>();
+        for handle in handles {
+            match handle.join() {
+                Ok(done) => {
+                    for (i, result) in done {
+                        results[i] = Some(result);
+                    }
+                }
+                Err(payload) => panic::resume_unwind(payload),
+            }
+        }
+    });
+    results.into_iter().map(Option::unwrap).collect()
+}
+
+/// `f` applied to every item in parallel, then folded with `reduce` starting
+/// from `init` in the order of `items`, so the answer doesn't depend on
+/// which thread finished first even if `reduce` isn't associative.
+pub fn map_reduce<T, R, A, F>(items: &[T], f: F, init: A, reduce: impl FnMut(A, R) -> A) -> A
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    map(items, f).into_iter().fold(init, reduce)
+}
@@ -0,0 +1,460 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// What is left to parse, remembering where it sits in the whole text so
+/// errors can point at a line and column.
+#[derive(Debug, Clone, Copy)]
+pub struct Input<'a> {
+    source: &'a str,
+    offset: usize,
+}
+
+impl<'a> Input<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Input { source, offset: 0 }
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.source[self.offset..]
+    }
+
+    fn advance(self, n: usize) -> Self {
+        Input {
+            offset: self.offset + n,
+            ..self
+        }
+    }
+
+    /// 1-based line and column (in chars) of the next character.
+    pub fn position(&self) -> (usize, usize) {
+        let before = &self.source[..self.offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (line, before[line_start..].chars().count() + 1)
+    }
+
+    /// The unexpected token for an error message: the next word, line break or end.
+    fn token(&self) -> String {
+        let rest = self.rest();
+        match rest.chars().next() {
+            None => String::from("end of input"),
+            Some('\n' | '\r') => String::from("line break"),
+            Some(c) if c.is_whitespace() => format!("{:?}", c),
+            Some(_) => {
+                let word = rest.split(char::is_whitespace).next().unwrap();
+                format!("{:?}", word)
+            }
+        }
+    }
+
+    fn error(&self, expected: impl Into<String>) -> ParseError {
+        let (line, column) = self.position();
+        ParseError {
+            line,
+            column,
+            expected: expected.into(),
+            found: self.token(),
+            offset: self.offset,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+    /// How far the failing parser got, so a value that was read but then
+    /// rejected counts as consumed input rather than a missing one.
+    offset: usize,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found {}",
+            self.line, self.column, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type PResult<'a, T> = Result<(T, Input<'a>), ParseError>;
+
+/// Anything that can read a `T` off the front of an [`Input`]. Plain
+/// `Fn(Input) -> PResult` closures are parsers, the combinators below build bigger ones.
+pub trait Parser<'a, T> {
+    fn parse(&self, input: Input<'a>) -> PResult<'a, T>;
+
+    fn map<U>(self, f: impl Fn(T) -> U) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+    {
+        move |input: Input<'a>| self.parse(input).map(|(v, rest)| (f(v), rest))
+    }
+
+    /// Like [`Parser::map`] but `f` can reject the value, the error then
+    /// points at where this parser started and `f`'s message is what was expected.
+    fn try_map<U>(self, f: impl Fn(T) -> Result<U, String>) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+    {
+        move |input: Input<'a>| {
+            let (v, rest) = self.parse(input)?;
+            f(v).map(|v| (v, rest)).map_err(|expected| ParseError {
+                offset: rest.offset,
+                ..input.error(expected)
+            })
+        }
+    }
+
+    /// Runs `next` after this one and keeps both values.
+    fn and<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, (T, U)>
+    where
+        Self: Sized,
+    {
+        move |input: Input<'a>| {
+            let (a, rest) = self.parse(input)?;
+            let (b, rest) = next.parse(rest)?;
+            Ok(((a, b), rest))
+        }
+    }
+
+    /// Runs `next` after this one and only keeps this one's value.
+    fn skip<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+    {
+        self.and(next).map(|(a, _)| a)
+    }
+
+    /// Falls back to `other` when this fails, when both fail at the same spot
+    /// the error lists both expectations.
+    fn or(self, other: impl Parser<'a, T>) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+    {
+        move |input: Input<'a>| match self.parse(input) {
+            Ok(result) => Ok(result),
+            Err(first) => other.parse(input).map_err(|second| {
+                if first.offset == second.offset {
+                    ParseError {
+                        expected: format!("{} or {}", first.expected, second.expected),
+                        ..second
+                    }
+                } else if first.offset > second.offset {
+                    first
+                } else {
+                    second
+                }
+            }),
+        }
+    }
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(Input<'a>) -> PResult<'a, T>,
+{
+    fn parse(&self, input: Input<'a>) -> PResult<'a, T> {
+        self(input)
+    }
+}
+
+/// Parses the whole of `source`, only trailing whitespace may be left over.
+pub fn parse_all<'a, T>(parser: impl Parser<'a, T>, source: &'a str) -> Result<T, ParseError> {
+    let (value, rest) = parser.parse(Input::new(source))?;
+    let trailing = rest.rest().len() - rest.rest().trim_start().len();
+    let rest = rest.advance(trailing);
+    if rest.rest().is_empty() {
+        Ok(value)
+    } else {
+        Err(rest.error("end of input"))
+    }
+}
+
+/// Exactly the text `expected`.
+pub fn tag<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| {
+        if input.rest().starts_with(expected) {
+            Ok((
+                &input.rest()[..expected.len()],
+                input.advance(expected.len()),
+            ))
+        } else {
+            Err(input.error(format!("{:?}", expected)))
+        }
+    }
+}
+
+/// The longest run, at least one char long, of chars matching `predicate`,
+/// `what` names it in errors.
+pub fn take_while1<'a>(
+    what: &'static str,
+    predicate: impl Fn(char) -> bool,
+) -> impl Parser<'a, &'a str> {
+    move |input: Input<'a>| {
+        let rest = input.rest();
+        let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        if len == 0 {
+            Err(input.error(what))
+        } else {
+            Ok((&rest[..len], input.advance(len)))
+        }
+    }
+}
+
+/// Letters and digits, e.g. a node name.
+pub fn word<'a>() -> impl Parser<'a, &'a str> {
+    take_while1("a word", char::is_alphanumeric)
+}
+
+pub fn digits<'a>() -> impl Parser<'a, &'a str> {
+    take_while1("a number", |c| c.is_ascii_digit())
+}
+
+/// Unsigned decimal number, rejects values that don't fit in `T`.
+pub fn number<'a, T: FromStr>() -> impl Parser<'a, T> {
+    digits().try_map(|v| {
+        v.parse::<T>()
+            .map_err(|_| format!("a number that fits in {}", std::any::type_name::<T>()))
+    })
+}
+
+/// Decimal number with an optional leading `-`.
+pub fn signed<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: Input<'a>| {
+        let sign = usize::from(input.rest().starts_with('-'));
+        let (v, rest) = digits().parse(input.advance(sign))?;
+        let text = &input.rest()[..sign + v.len()];
+        let value = text.parse::<T>().map_err(|_| ParseError {
+            offset: rest.offset,
+            ..input.error(format!(
+                "a number that fits in {}",
+                std::any::type_name::<T>()
+            ))
+        })?;
+        Ok((value, rest))
+    }
+}
+
+/// Zero or more spaces or tabs, never a line break.
+pub fn spaces<'a>() -> impl Parser<'a, ()> {
+    move |input: Input<'a>| {
+        let rest = input.rest();
+        let len = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        Ok(((), input.advance(len)))
+    }
+}
+
+/// `\n` or `\r\n`.
+pub fn newline<'a>() -> impl Parser<'a, ()> {
+    move |input: Input<'a>| {
+        let rest = input.rest();
+        match rest
+            .strip_prefix('\n')
+            .or_else(|| rest.strip_prefix("\r\n"))
+        {
+            Some(after) => Ok(((), input.advance(rest.len() - after.len()))),
+            None => Err(input.error("line break")),
+        }
+    }
+}
+
+/// `parser` preceded by `prefix`, whose value is dropped.
+pub fn preceded<'a, P, T>(
+    prefix: impl Parser<'a, P>,
+    parser: impl Parser<'a, T>,
+) -> impl Parser<'a, T> {
+    prefix.and(parser).map(|(_, v)| v)
+}
+
+/// `parser`, or `None` if it fails without consuming anything.
+pub fn opt<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Option<T>> {
+    move |input: Input<'a>| match parser.parse(input) {
+        Ok((v, rest)) => Ok((Some(v), rest)),
+        Err(e) if e.offset == input.offset => Ok((None, input)),
+        Err(e) => Err(e),
+    }
+}
+
+/// One or more `item`s with `separator` in between. The list ends when an
+/// item fails right after a separator without consuming anything, an item
+/// failing halfway through is an error.
+pub fn separated<'a, T, S>(
+    item: impl Parser<'a, T>,
+    separator: impl Parser<'a, S>,
+) -> impl Parser<'a, Vec<T>> {
+    move |input: Input<'a>| {
+        let (first, mut rest) = item.parse(input)?;
+        let mut items = vec![first];
+        loop {
+            let Ok((_, after_separator)) = separator.parse(rest) else {
+                break;
+            };
+            match item.parse(after_separator) {
+                Ok((v, after_item)) => {
+                    items.push(v);
+                    rest = after_item;
+                }
+                Err(e) if e.offset == after_separator.offset => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok((items, rest))
+    }
+}
+
+/// Space separated numbers, leading spaces allowed.
+pub fn numbers<'a, T: FromStr>() -> impl Parser<'a, Vec<T>> {
+    preceded(spaces(), separated(number(), spaces()))
+}
+
+/// `label:` followed by `body`, either on the same line or starting on the next one.
+pub fn section<'a, L, T>(
+    label: impl Parser<'a, L>,
+    body: impl Parser<'a, T>,
+) -> impl Parser<'a, T> {
+    let header = label.skip(tag(":")).skip(spaces()).skip(opt(newline()));
+    preceded(header, body)
+}
+
+/// `key = (left, right)`, spaces around `=` and after `,` are optional.
+pub fn pair_assignment<'a, K, A, B>(
+    key: impl Parser<'a, K>,
+    left: impl Parser<'a, A>,
+    right: impl Parser<'a, B>,
+) -> impl Parser<'a, (K, (A, B))> {
+    let pair = preceded(tag("("), left)
+        .skip(spaces())
+        .skip(tag(","))
+        .skip(spaces())
+        .and(right)
+        .skip(tag(")"));
+    key.skip(spaces()).skip(tag("=")).skip(spaces()).and(pair)
+}
+
+/// Consecutive lines each read by `item`, up to a blank line or the end of input.
+pub fn lines<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: Input<'a>| {
+        let (first, mut rest) = item.parse(input)?;
+        let mut items = vec![first];
+        while let Ok((_, next_line)) = newline().parse(rest) {
+            if next_line.rest().trim().is_empty() || newline().parse(next_line).is_ok() {
+                break;
+            }
+            let (v, after) = item.parse(next_line)?;
+            items.push(v);
+            rest = after;
+        }
+        Ok((items, rest))
+    }
+}
+
+/// One or more empty lines after the end of the current one.
+pub fn blank_line<'a>() -> impl Parser<'a, ()> {
+    move |input: Input<'a>| {
+        let (_, mut rest) = newline().parse(input)?;
+        let (_, after) = newline().parse(rest)?;
+        rest = after;
+        while let Ok((_, after)) = newline().parse(rest) {
+            rest = after;
+        }
+        Ok(((), rest))
+    }
+}
+
+/// Blocks read by `item` separated by blank lines, up to the end of input.
+pub fn blocks<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: Input<'a>| {
+        let (first, mut rest) = item.parse(input)?;
+        let mut items = vec![first];
+        while let Ok((_, next_block)) = blank_line().parse(rest) {
+            if next_block.rest().trim().is_empty() {
+                break;
+            }
+            let (v, after) = item.parse(next_block)?;
+            items.push(v);
+            rest = after;
+        }
+        Ok((items, rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_at(err: &ParseError) -> (usize, usize, &str, &str) {
+        (err.line, err.column, &err.expected, &err.found)
+    }
+
+    #[test]
+    fn reports_bad_token_in_a_network_line() {
+        let network = word()
+            .skip(blank_line())
+            .and(lines(pair_assignment(word(), word(), word())));
+        let input = "LR\n\nAAA = (BBB, CCC)\nBBB = (DDD; EEE)\nCCC = (ZZZ, ZZZ)\n";
+        let err = parse_all(network, input).unwrap_err();
+        assert_eq!(error_at(&err), (4, 11, "\",\"", "\";\""));
+        assert_eq!(
+            err.to_string(),
+            "line 4, column 11: expected \",\", found \";\""
+        );
+    }
+
+    #[test]
+    fn reports_bad_number_in_a_later_block() {
+        let row = number::<i64>().skip(spaces()).and(number::<i64>());
+        let heading = take_while1("a map name", |c| c != ':' && c != '\n');
+        let maps = blocks(section(heading, lines(row)));
+        let input = "a-to-b map:\n50 98\n52 50\n\nb-to-c map:\n0 15\n3 x1\n";
+        let err = parse_all(maps, input).unwrap_err();
+        assert_eq!(error_at(&err), (7, 3, "a number", "\"x1\""));
+    }
+
+    #[test]
+    fn reports_numbers_that_do_not_fit() {
+        let err = parse_all(numbers::<u8>(), "1 2 300").unwrap_err();
+        assert_eq!(
+            error_at(&err),
+            (1, 5, "a number that fits in u8", "\"300\"")
+        );
+    }
+
+    #[test]
+    fn reports_line_breaks_end_of_input_and_leftovers() {
+        let pair = word().skip(tag(":")).and(number::<u32>());
+        let err = parse_all(lines(pair), "a:1\nb:\n").unwrap_err();
+        assert_eq!(error_at(&err), (2, 3, "a number", "line break"));
+        let err = parse_all(tag("ab"), "a").unwrap_err();
+        assert_eq!(error_at(&err), (1, 1, "\"ab\"", "\"a\""));
+        let err = parse_all(word(), "").unwrap_err();
+        assert_eq!(error_at(&err), (1, 1, "a word", "end of input"));
+        let err = parse_all(word(), "abc def").unwrap_err();
+        assert_eq!(error_at(&err), (1, 5, "end of input", "\"def\""));
+    }
+
+    #[test]
+    fn alternatives_failing_at_the_same_spot_list_both() {
+        let sign = tag("+").or(tag("-"));
+        let err = parse_all(sign, "*").unwrap_err();
+        assert_eq!(error_at(&err), (1, 1, "\"+\" or \"-\"", "\"*\""));
+    }
+
+    #[test]
+    fn parses_well_formed_input() {
+        let input = "Time:      7  15   30\r\nDistance:  9  40  200\r\n";
+        let races = section(tag("Time"), numbers::<u32>())
+            .skip(newline())
+            .and(section(tag("Distance"), numbers::<u32>()));
+        assert_eq!(
+            parse_all(races, input),
+            Ok((vec![7, 15, 30], vec![9, 40, 200]))
+        );
+        assert_eq!(parse_all(signed::<i32>(), "-42"), Ok(-42));
+    }
+}
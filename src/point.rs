@@ -0,0 +1,141 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A signed 2D position, `x` grows to the right (columns) and `y` grows
+/// downwards (rows), so `Point::new(x, y)` addresses `grid[y][x]`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    pub fn manhattan(&self, other: Point) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, k: i64) -> Point {
+        Point::new(self.x * k, self.y * k)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/// One step along a grid axis. Uses the same screen convention as [`Point`],
+/// so `Up` decreases `y` and turning clockwise goes `Up -> Right -> Down -> Left`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Every direction, clockwise starting from `Up`.
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    pub fn offset(&self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+
+    pub fn cw(&self) -> Direction {
+        Direction::ALL[(*self as usize + 1) % 4]
+    }
+
+    pub fn ccw(&self) -> Direction {
+        Direction::ALL[(*self as usize + 3) % 4]
+    }
+
+    pub fn opposite(&self) -> Direction {
+        Direction::ALL[(*self as usize + 2) % 4]
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        !self.is_vertical()
+    }
+
+    /// A distinct bit per direction, for packing a set of directions in a `u8`.
+    pub fn flag(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = String;
+
+    /// Accepts both `U/R/D/L` and compass `N/E/S/W` letters.
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'U' | 'N' => Ok(Direction::Up),
+            'R' | 'E' => Ok(Direction::Right),
+            'D' | 'S' => Ok(Direction::Down),
+            'L' | 'W' => Ok(Direction::Left),
+            _ => Err(format!("{:?} is not a direction", c)),
+        }
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Point;
+
+    fn add(self, dir: Direction) -> Point {
+        self + dir.offset()
+    }
+}
+
+impl AddAssign<Direction> for Point {
+    fn add_assign(&mut self, dir: Direction) {
+        *self = *self + dir;
+    }
+}
@@ -0,0 +1,88 @@
+use crate::solutions::*;
+use crate::validate::Assumption;
+
+pub type SolutionFn = fn(&String) -> String;
+
+/// Renders a day's input as Graphviz DOT source.
+pub type GraphFn = fn(&str) -> String;
+
+pub struct Day {
+    pub title: &'static str,
+    pub parts: [Option<SolutionFn>; 2],
+    /// Input properties the solutions rely on, checked by `validate`.
+    pub assumptions: &'static [Assumption],
+    /// Export of the input as a graph, written by `--dot`.
+    pub graph: Option<GraphFn>,
+}
+
+impl Day {
+    pub fn implemented(&self) -> impl Iterator<Item = (usize, SolutionFn)> + '_ {
+        self.parts
+            .iter()
+            .enumerate()
+            .filter_map(|(i, part)| part.map(|f| (i + 1, f)))
+    }
+}
+
+/// `day!(title, module, flags...)` where the flags say what else the module
+/// provides: `validated` for `ASSUMPTIONS` and `graph` for a `dot` export.
+macro_rules! day {
+    (@validated $day:ident, $module:ident) => {
+        $day.assumptions = $module::ASSUMPTIONS;
+    };
+    (@graph $day:ident, $module:ident) => {
+        $day.graph = Some($module::dot);
+    };
+    ($title:expr, $module:ident $(, $flag:ident)*) => {{
+        #[allow(unused_mut)]
+        let mut day = Day {
+            title: $title,
+            parts: [Some($module::p1), Some($module::p2)],
+            assumptions: &[],
+            graph: None,
+        };
+        $(day!(@$flag day, $module);)*
+        day
+    }};
+    ($title:expr) => {
+        Day {
+            title: $title,
+            parts: [None, None],
+            assumptions: &[],
+            graph: None,
+        }
+    };
+}
+
+pub static DAYS: [Day; 25] = [
+    day!("Trebuchet?!", day01),
+    day!("Cube Conundrum", day02),
+    day!("Gear Ratios", day03, graph),
+    day!("Scratchcards", day04),
+    day!("If You Give A Seed A Fertilizer", day05),
+    day!("Wait For It", day06, validated),
+    day!("Camel Cards", day07),
+    day!("Haunted Wasteland", day08, validated, graph),
+    day!("Mirage Maintenance", day09),
+    day!("Pipe Maze", day10, validated, graph),
+    day!("Cosmic Expansion", day11),
+    day!("Hot Springs", day12),
+    day!("Point of Incidence", day13, validated),
+    day!("Parabolic Reflector Dish", day14),
+    day!("Lens Library", day15),
+    day!("The Floor Will Be Lava", day16),
+    day!("Clumsy Crucible", day17),
+    day!("Lavaduct Lagoon"),
+    day!("Aplenty"),
+    day!("Pulse Propagation"),
+    day!("Step Counter"),
+    day!("Sand Slabs"),
+    day!("A Long Walk"),
+    day!("Never Tell Me The Odds"),
+    day!("Snowverload"),
+];
+
+/// Where `bench` looks for the puzzle input of a given day (1-based).
+pub fn input_path(day: usize) -> String {
+    format!("inputs/day{:02}.txt", day)
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+use aoc2023::registry::DAYS;
+
+use crate::bench::{self, Benchmarks};
+
+pub const ANSWERS_PATH: &str = "answers.txt";
+pub const REPORT_PATH: &str = "REPORT.md";
+
+type Answers = BTreeMap<(usize, usize), String>;
+
+/// Reads the known-good answers, one `<day> <part> <answer>` entry per line.
+fn load_answers(path: &str) -> Answers {
+    let mut result = Answers::new();
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return result;
+    };
+    for line in content.lines().filter(|l| !l.trim().is_empty() && !l.starts_with('#')) {
+        let [day, part, answer] = line.splitn(3, ' ').collect_vec()[..] else {
+            panic!("malformed line in {}: {}", path, line);
+        };
+        result.insert(
+            (day.parse().unwrap(), part.parse().unwrap()),
+            answer.trim().to_string(),
+        );
+    }
+    result
+}
+
+fn format_ns(ns: u128) -> String {
+    match ns {
+        0..=999 => format!("{} ns", ns),
+        1_000..=999_999 => format!("{:.1} µs", ns as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.2} ms", ns as f64 / 1e6),
+        _ => format!("{:.2} s", ns as f64 / 1e9),
+    }
+}
+
+fn check_status(expected: Option<&String>, got: Option<&bench::Entry>) -> &'static str {
+    match (expected, got) {
+        (_, None) => "not run",
+        (None, Some(_)) => "unchecked",
+        (Some(expected), Some(got)) if *expected == got.answer => "ok",
+        (Some(_), Some(_)) => "WRONG",
+    }
+}
+
+/// Renders the progress table. The output only depends on the registry and the
+/// stored answers and benchmarks, so regenerating it without new data is a no-op.
+fn render(answers: &Answers, benchmarks: &Benchmarks) -> String {
+    let mut lines = vec![
+        String::from("| Day | Title | Parts | Check | Median |"),
+        String::from("|----:|-------|-------|-------|-------:|"),
+    ];
+    let mut total_ns = 0;
+    for (i, day) in DAYS.iter().enumerate() {
+        let day_number = i + 1;
+        let parts = day.implemented().map(|(part, _)| part).collect_vec();
+        let check = parts
+            .iter()
+            .map(|&part| {
+                let key = (day_number, part);
+                format!(
+                    "p{} {}",
+                    part,
+                    check_status(answers.get(&key), benchmarks.get(&key))
+                )
+            })
+            .join(", ");
+        let timings = parts
+            .iter()
+            .filter_map(|&part| benchmarks.get(&(day_number, part)))
+            .map(|entry| entry.median_ns)
+            .collect_vec();
+        let median = if timings.is_empty() {
+            String::from("-")
+        } else {
+            let day_ns = timings.iter().sum::<u128>();
+            total_ns += day_ns;
+            format_ns(day_ns)
+        };
+        lines.push(format!(
+            "| {:02} | {} | {} | {} | {} |",
+            day_number,
+            day.title,
+            if parts.is_empty() { String::from("-") } else { parts.iter().join(", ") },
+            if check.is_empty() { String::from("-") } else { check },
+            median,
+        ));
+    }
+    lines.push(format!("| | **Total** | | | {} |", format_ns(total_ns)));
+
+    format!("# Advent of Code 2023\n\n{}\n", lines.join("\n"))
+}
+
+pub fn write(path: &str) {
+    let report = render(&load_answers(ANSWERS_PATH), &bench::load(bench::BENCHMARKS_PATH));
+    std::fs::write(path, report).unwrap_or_else(|e| panic!("couldn't write {}: {}", path, e));
+}
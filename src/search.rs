@@ -0,0 +1,331 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Path costs, `Default` is the cost of going nowhere.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<C: Copy + Ord + Add<Output = C> + Default> Cost for C {}
+
+struct Node<S, C> {
+    state: S,
+    cost: C,
+    parent: Option<usize>,
+}
+
+/// Everything a best-first search settled: the cheapest cost of each state
+/// it reached and, if asked for, the way back to a start.
+pub struct Search<S, C> {
+    index: HashMap<S, usize>,
+    nodes: Vec<Node<S, C>>,
+    goal: Option<usize>,
+    track_paths: bool,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> Search<S, C> {
+    /// The first goal state popped and its cost, which is the cheapest one.
+    pub fn goal(&self) -> Option<(&S, C)> {
+        self.goal.map(|i| (&self.nodes[i].state, self.nodes[i].cost))
+    }
+
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.index.get(state).map(|&i| self.nodes[i].cost)
+    }
+
+    /// Best known cost of every state seen, including ones only pushed and never expanded.
+    pub fn costs(&self) -> impl Iterator<Item = (&S, C)> {
+        self.nodes.iter().map(|node| (&node.state, node.cost))
+    }
+
+    /// States from a start to `state`, both included. `None` if `state`
+    /// wasn't reached or the search didn't track paths.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.track_paths {
+            return None;
+        }
+        let mut path = Vec::new();
+        let mut current = self.index.get(state).copied();
+        while let Some(i) = current {
+            path.push(self.nodes[i].state.clone());
+            current = self.nodes[i].parent;
+        }
+        path.reverse();
+        Some(path).filter(|path| !path.is_empty())
+    }
+}
+
+/// Best-first search from every state in `starts`. `successors` lists the
+/// states one move away with the cost of that move, `heuristic` must never
+/// overestimate the remaining cost (return the default cost for plain
+/// Dijkstra). Stops at the first state `is_goal` accepts, or explores
+/// everything reachable if it accepts none.
+pub fn search<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+    track_paths: bool,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut result = Search {
+        index: HashMap::new(),
+        nodes: Vec::new(),
+        goal: None,
+        track_paths,
+    };
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        if let Entry::Vacant(entry) = result.index.entry(start.clone()) {
+            entry.insert(result.nodes.len());
+            heap.push(Reverse((heuristic(&start), C::default(), result.nodes.len())));
+            result.nodes.push(Node {
+                state: start,
+                cost: C::default(),
+                parent: None,
+            });
+        }
+    }
+
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        if cost > result.nodes[i].cost {
+            continue;
+        }
+        let state = result.nodes[i].state.clone();
+        if is_goal(&state) {
+            result.goal = Some(i);
+            break;
+        }
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            let j = match result.index.entry(next) {
+                Entry::Vacant(entry) => {
+                    let j = result.nodes.len();
+                    result.nodes.push(Node {
+                        state: entry.key().clone(),
+                        cost: next_cost,
+                        parent: None,
+                    });
+                    entry.insert(j);
+                    j
+                }
+                Entry::Occupied(entry) => {
+                    let j = *entry.get();
+                    if next_cost >= result.nodes[j].cost {
+                        continue;
+                    }
+                    result.nodes[j].cost = next_cost;
+                    j
+                }
+            };
+            if track_paths {
+                result.nodes[j].parent = Some(i);
+            }
+            let priority = next_cost + heuristic(&result.nodes[j].state);
+            heap.push(Reverse((priority, next_cost, j)));
+        }
+    }
+    result
+}
+
+/// Cheapest goal state reachable from `starts` and its cost.
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<(S, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let result = search(starts, successors, |_| C::default(), is_goal, false);
+    result.goal().map(|(state, cost)| (state.clone(), cost))
+}
+
+/// Like [`dijkstra`] but guided by an admissible `heuristic`.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<(S, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let result = search(starts, successors, heuristic, is_goal, false);
+    result.goal().map(|(state, cost)| (state.clone(), cost))
+}
+
+/// Cheapest path from a start to a goal state, both included, and its cost.
+pub fn astar_path<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<(Vec<S>, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let result = search(starts, successors, heuristic, is_goal, true);
+    let (goal, cost) = result.goal()?;
+    Some((result.path_to(goal)?, cost))
+}
+
+/// Cheapest cost to every state reachable from `starts`.
+pub fn dijkstra_all<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+) -> HashMap<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let result = search(starts, successors, |_| C::default(), |_| false, false);
+    result
+        .nodes
+        .into_iter()
+        .map(|node| (node.state, node.cost))
+        .collect()
+}
+
+/// Number of moves to every state reachable from `starts` when every move costs the same.
+pub fn bfs_distances<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if distances.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+    while let Some(state) = queue.pop_front() {
+        let distance = distances[&state];
+        for next in neighbours(&state) {
+            if let Entry::Vacant(entry) = distances.entry(next) {
+                queue.push_back(entry.key().clone());
+                entry.insert(distance + 1);
+            }
+        }
+    }
+    distances
+}
+
+/// Cheapest cost to every state reachable from `starts` when moves are
+/// either free (`false`) or cost one (`true`).
+pub fn zero_one_bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, bool)>,
+{
+    let mut distances = HashMap::new();
+    let mut deque = VecDeque::new();
+    for start in starts {
+        distances.insert(start.clone(), 0);
+        deque.push_back((start, 0));
+    }
+    while let Some((state, distance)) = deque.pop_front() {
+        if distance > distances[&state] {
+            continue;
+        }
+        for (next, costs_one) in successors(&state) {
+            let next_distance = distance + usize::from(costs_one);
+            if distances.get(&next).is_some_and(|&d| d <= next_distance) {
+                continue;
+            }
+            distances.insert(next.clone(), next_distance);
+            if costs_one {
+                deque.push_back((next, next_distance));
+            } else {
+                deque.push_front((next, next_distance));
+            }
+        }
+    }
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0 -> 2 -> 1 -> 3 -> 4 is the cheapest way to 4, 5 is unreachable.
+    fn edges(node: &usize) -> Vec<(usize, u32)> {
+        match node {
+            0 => vec![(1, 4), (2, 1)],
+            1 => vec![(3, 1)],
+            2 => vec![(1, 2), (3, 5)],
+            3 => vec![(4, 3)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn dijkstra_finds_cheapest_costs() {
+        assert_eq!(dijkstra([0], edges, |&n| n == 4), Some((4, 7)));
+        assert_eq!(dijkstra([0], edges, |&n| n == 5), None);
+        let all = dijkstra_all([0], edges);
+        let expected = HashMap::from([(0, 0), (1, 3), (2, 1), (3, 4), (4, 7)]);
+        assert_eq!(all, expected);
+        assert_eq!(dijkstra_all([0, 3], edges)[&4], 3);
+    }
+
+    #[test]
+    fn astar_follows_predecessors_back_to_the_start() {
+        let heuristic = |&n: &usize| 4u32.saturating_sub(n as u32);
+        assert_eq!(astar([0], edges, heuristic, |&n| n == 4), Some((4, 7)));
+        assert_eq!(
+            astar_path([0], edges, heuristic, |&n| n == 4),
+            Some((vec![0, 2, 1, 3, 4], 7))
+        );
+        let tracked = search([0], edges, |_| 0, |_| false, true);
+        assert_eq!(tracked.path_to(&3), Some(vec![0, 2, 1, 3]));
+        assert_eq!(tracked.path_to(&0), Some(vec![0]));
+        assert_eq!(tracked.path_to(&5), None);
+        let untracked = search([0], edges, |_| 0, |_| false, false);
+        assert_eq!(untracked.cost(&1), Some(3));
+        assert_eq!(untracked.path_to(&1), None);
+    }
+
+    #[test]
+    fn bfs_counts_moves() {
+        let ring = |&n: &usize| [(n + 1) % 6, (n + 5) % 6];
+        let expected = HashMap::from([(0, 0), (1, 1), (5, 1), (2, 2), (4, 2), (3, 3)]);
+        assert_eq!(bfs_distances([0], ring), expected);
+    }
+
+    #[test]
+    fn zero_one_bfs_prefers_free_moves() {
+        // Stepping onto an odd node costs one, and there is a free jump from 0 to 4.
+        let moves = |&n: &usize| {
+            let mut moves = Vec::new();
+            if n < 5 {
+                moves.push((n + 1, (n + 1) % 2 == 1));
+            }
+            if n == 0 {
+                moves.push((4, false));
+            }
+            moves
+        };
+        let expected = HashMap::from([(0, 0), (1, 1), (2, 1), (3, 2), (4, 0), (5, 1)]);
+        assert_eq!(zero_one_bfs([0], moves), expected);
+    }
+}
@@ -0,0 +1,216 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering::Relaxed};
+use std::sync::Mutex;
+
+use crate::cycle::{self, Cycle};
+use crate::{aoccount, trace};
+
+/// Steps between frames `--frames` asked for, 0 when it wasn't given.
+static FRAME_EVERY: AtomicUsize = AtomicUsize::new(0);
+static LOG_STEPS: AtomicBool = AtomicBool::new(false);
+static FRAMES: Mutex<Vec<(usize, String)>> = Mutex::new(Vec::new());
+
+/// A state that evolves one deterministic step at a time. Days implement
+/// the transition rule and [`Runner`] does the driving.
+pub trait Simulation {
+    fn step(&mut self);
+
+    /// Whether stepping further would change nothing.
+    fn is_done(&self) -> bool {
+        false
+    }
+
+    /// Fingerprint of the current state for cycle detection, `None` if the
+    /// simulation doesn't provide one and so can't be fast-forwarded.
+    fn fingerprint(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Gets to see the state after every step a [`Runner`] simulates, and the
+/// initial one as step 0.
+pub trait Observer<S> {
+    fn observe(&mut self, step: usize, state: &S);
+}
+
+impl<S, F: FnMut(usize, &S)> Observer<S> for F {
+    fn observe(&mut self, step: usize, state: &S) {
+        self(step, state)
+    }
+}
+
+/// Renders the state every `every` steps, for visualising a run afterwards.
+pub struct FrameCapture {
+    every: usize,
+    pub frames: Vec<(usize, String)>,
+}
+
+impl FrameCapture {
+    pub fn new(every: usize) -> Self {
+        assert!(every > 0, "capturing every 0 steps");
+        FrameCapture {
+            every,
+            frames: Vec::new(),
+        }
+    }
+}
+
+impl<S: Display> Observer<S> for FrameCapture {
+    fn observe(&mut self, step: usize, state: &S) {
+        if step.is_multiple_of(self.every) {
+            self.frames.push((step, state.to_string()));
+        }
+    }
+}
+
+/// Prints the step number and the state to stderr after every step.
+pub struct DebugLog;
+
+impl<S: Display> Observer<S> for DebugLog {
+    fn observe(&mut self, step: usize, state: &S) {
+        eprintln!("step {}:\n{}", step, state);
+    }
+}
+
+/// Captures a frame every `every` steps in runs that attach [`CliObservers`].
+pub fn capture_frames(every: usize) {
+    FRAME_EVERY.store(every, Relaxed);
+}
+
+/// Logs every step of runs that attach [`CliObservers`].
+pub fn log_steps() {
+    LOG_STEPS.store(true, Relaxed);
+}
+
+/// Returns the frames captured so far, oldest first, and forgets them.
+pub fn take_frames() -> Vec<(usize, String)> {
+    std::mem::take(&mut *FRAMES.lock().unwrap())
+}
+
+/// The observers asked for on the command line, through [`capture_frames`]
+/// and [`log_steps`]. Captured frames are handed over to [`take_frames`]
+/// when this is dropped.
+pub struct CliObservers {
+    frames: Option<FrameCapture>,
+    log: Option<DebugLog>,
+}
+
+impl CliObservers {
+    pub fn new() -> Self {
+        let every = FRAME_EVERY.load(Relaxed);
+        CliObservers {
+            frames: (every > 0).then(|| FrameCapture::new(every)),
+            log: LOG_STEPS.load(Relaxed).then_some(DebugLog),
+        }
+    }
+
+    pub fn attach<'a, S: Simulation + Display>(
+        &'a mut self,
+        mut runner: Runner<'a, S>,
+    ) -> Runner<'a, S> {
+        if let Some(frames) = &mut self.frames {
+            runner = runner.observe(frames);
+        }
+        if let Some(log) = &mut self.log {
+            runner = runner.observe(log);
+        }
+        runner
+    }
+}
+
+impl Default for CliObservers {
+    fn default() -> Self {
+        CliObservers::new()
+    }
+}
+
+impl Drop for CliObservers {
+    fn drop(&mut self) {
+        if let Some(frames) = self.frames.take() {
+            FRAMES.lock().unwrap().extend(frames.frames);
+        }
+    }
+}
+
+/// Drives a [`Simulation`], telling observers about every step and timing
+/// them as trace spans if asked to.
+pub struct Runner<'a, S> {
+    state: S,
+    steps: usize,
+    observers: Vec<&'a mut dyn Observer<S>>,
+    span: Option<&'static str>,
+}
+
+impl<'a, S: Simulation> Runner<'a, S> {
+    pub fn new(state: S) -> Self {
+        Runner {
+            state,
+            steps: 0,
+            observers: Vec::new(),
+            span: None,
+        }
+    }
+
+    /// Adds an observer, which sees the current state right away.
+    pub fn observe(mut self, observer: &'a mut dyn Observer<S>) -> Self {
+        observer.observe(self.steps, &self.state);
+        self.observers.push(observer);
+        self
+    }
+
+    /// Records every step as a trace span called `name`.
+    pub fn traced(mut self, name: &'static str) -> Self {
+        self.span = Some(name);
+        self
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn into_state(self) -> S {
+        self.state
+    }
+
+    /// Number of steps the state is past the initial one.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn step(&mut self) {
+        {
+            let _span = self.span.map(trace::span);
+            self.state.step();
+        }
+        aoccount!("simulated steps");
+        self.steps += 1;
+        for observer in self.observers.iter_mut() {
+            observer.observe(self.steps, &self.state);
+        }
+    }
+
+    /// Steps until the simulation is done, returns how many steps that took.
+    pub fn run_until_done(&mut self) -> usize {
+        let start = self.steps;
+        while !self.state.is_done() {
+            self.step();
+        }
+        self.steps - start
+    }
+
+    /// Steps until the state is the one after `target` steps in total,
+    /// skipping whole loops once a fingerprint repeats. Observers only see
+    /// the steps actually simulated. Returns the cycle if one was found,
+    /// panics if the simulation has no fingerprint.
+    pub fn fast_forward(&mut self, target: usize) -> Option<Cycle> {
+        assert!(target >= self.steps, "can't go back to step {}", target);
+        let cycle = cycle::fast_forward(self, target - self.steps, Runner::step, |runner| {
+            runner
+                .state
+                .fingerprint()
+                .expect("fast-forwarding a simulation without a fingerprint")
+        });
+        self.steps = target;
+        cycle
+    }
+}
@@ -1,3 +1,5 @@
+use crate::parse::{lines, number, parse_all, preceded, separated, spaces, tag, Parser};
+
 #[derive(Debug)]
 enum Cube {
     Red(i32),
@@ -12,51 +14,22 @@ struct Game {
 }
 
 fn parse_input(input: &String) -> Vec<Game> {
-    let mut result = Vec::new();
-    for line in input.lines() {
-        let gameid2data = line
-            .split(":")
-            .into_iter()
-            .map(|piece| {
-                if piece.contains("Game") {
-                    piece.split(" ").collect::<Vec<&str>>()[1].into()
-                } else {
-                    String::from(piece)
-                }
-            })
-            .map(|v| v.trim().into())
-            .collect::<Vec<String>>();
-        let id: i32 = gameid2data[0].parse().unwrap();
-        let rounds: Vec<Vec<Cube>> = gameid2data[1]
-            .split(";")
-            .map(|r| {
-                r.split(",")
-                    .map(|v| v.trim().into())
-                    .collect::<Vec<String>>()
-            })
-            .map(|hand| {
-                hand.iter().fold(
-                    Vec::<Cube>::new(),
-                    |mut value: Vec<Cube>, single_draw: &String| {
-                        let values = single_draw
-                            .split(" ")
-                            .map(|v| v.into())
-                            .collect::<Vec<String>>();
-                        let result = match values[1].as_str() {
-                            "red" => Cube::Red(values[0].parse().unwrap()),
-                            "green" => Cube::Green(values[0].parse().unwrap()),
-                            "blue" => Cube::Blue(values[0].parse().unwrap()),
-                            _ => unreachable!(),
-                        };
-                        value.push(result);
-                        value
-                    },
-                )
-            })
-            .collect();
-        result.push(Game { id, rounds })
-    }
-    result
+    let colour = tag("red").or(tag("green")).or(tag("blue"));
+    let cube = number::<i32>()
+        .skip(spaces())
+        .and(colour)
+        .map(|(n, colour)| match colour {
+            "red" => Cube::Red(n),
+            "green" => Cube::Green(n),
+            _ => Cube::Blue(n),
+        });
+    let round = separated(cube, tag(",").skip(spaces()));
+    let game = preceded(tag("Game").skip(spaces()), number::<i32>())
+        .skip(tag(":"))
+        .skip(spaces())
+        .and(separated(round, tag(";").skip(spaces())))
+        .map(|(id, rounds)| Game { id, rounds });
+    parse_all(lines(game), input).unwrap_or_else(|e| panic!("{}", e))
 }
 
 const PRESET_NO_RED: i32 = 12;
@@ -1,53 +1,54 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 
-fn parse_number(grid: &Vec<Vec<char>>, i: usize, j: usize) -> (u32, usize) {
-    let result: String = grid[i]
+use crate::dot::{DotGraph, GraphExport};
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+
+type Schematic = Grid<char>;
+
+fn parse_number(grid: &Schematic, p: Point) -> (u32, usize) {
+    let result: String = grid
+        .row(p.y as usize)
         .iter()
-        .skip(j)
+        .skip(p.x as usize)
         .take_while(|c| c.is_numeric())
         .collect();
     (result.parse::<u32>().unwrap(), result.len())
 }
 
-fn find_adjs_by<F>(grid: &Vec<Vec<char>>, i: usize, j: usize, adj_rule: F) -> Vec<(usize, usize)>
+fn find_adjs_by<F>(grid: &Schematic, p: Point, adj_rule: F) -> Vec<Point>
 where
     F: Fn(char) -> bool,
 {
-    let mut result = Vec::new();
-    let (ii, jj) = (i, j);
-    for i in (if i > 0 { i - 1 } else { i })..=(if i < grid.len() - 1 { i + 1 } else { i }) {
-        for j in (if j > 0 { j - 1 } else { j })..=(if j < grid[i].len() - 1 { j + 1 } else { j }) {
-            if !(ii == i && jj == j) && adj_rule(grid[i][j]) {
-                result.push((i, j));
-            }
-        }
-    }
-    result
+    grid.neighbours8(p).filter(|&n| adj_rule(grid[n])).collect()
+}
+
+fn is_symbol(c: char) -> bool {
+    c != '.' && !c.is_numeric()
 }
 
-fn part_has_adj_special(grid: &Vec<Vec<char>>, i: usize, j: usize) -> bool {
-    if j >= grid[i].len() || !grid[i][j].is_numeric() {
+fn part_has_adj_special(grid: &Schematic, p: Point) -> bool {
+    if !grid.get(p).is_some_and(|c| c.is_numeric()) {
         return false;
     }
 
-    if find_adjs_by(grid, i, j, |c| c != '.' && !c.is_numeric()).len() > 0 {
+    if !find_adjs_by(grid, p, is_symbol).is_empty() {
         return true;
     }
 
-    part_has_adj_special(grid, i, j + 1)
+    part_has_adj_special(grid, p + Direction::Right)
 }
 
-fn find_gear_adj_parts(grid: &Vec<Vec<char>>, i: usize, j: usize) -> Option<Vec<u32>> {
-    if grid[i][j] != '*' {
+fn find_gear_adj_parts(grid: &Schematic, p: Point) -> Option<Vec<u32>> {
+    if grid[p] != '*' {
         return None;
     }
-    let part_positions = find_adjs_by(grid, i, j, |c| c.is_numeric());
+    let part_positions = find_adjs_by(grid, p, |c| c.is_numeric());
     Some(
         HashSet::<u32>::from_iter(
             part_positions
                 .into_iter()
-                .map(|(i, j)| find_whole_number(&grid, i, j))
-                .into_iter(),
+                .map(|p| find_whole_number(grid, p)),
         )
         .into_iter()
         .collect(),
@@ -55,63 +56,89 @@ fn find_gear_adj_parts(grid: &Vec<Vec<char>>, i: usize, j: usize) -> Option<Vec<
 }
 
 pub fn p1(input: &String) -> String {
-    let grid: Vec<Vec<char>> = input.lines().map(|v| v.chars().collect()).collect();
+    let grid = Grid::parse(input, |c| c);
     let mut numbers = Vec::new();
 
-    for i in 0..grid.len() {
-        let mut skipj = 0;
-        for j in 0..grid[i].len() {
-            if skipj > 0 {
-                skipj -= 1;
-                continue;
-            }
+    let mut skip = 0;
+    for p in grid.points() {
+        if p.x == 0 {
+            skip = 0;
+        }
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
 
-            if grid[i][j].is_numeric() && part_has_adj_special(&grid, i, j) {
-                let (n, skip) = parse_number(&grid, i, j);
-                numbers.push(n);
-                skipj = skip;
-            }
+        if grid[p].is_numeric() && part_has_adj_special(&grid, p) {
+            let (n, len) = parse_number(&grid, p);
+            numbers.push(n);
+            skip = len;
         }
     }
 
     numbers.iter().sum::<u32>().to_string()
 }
 
-fn find_whole_number(grid: &Vec<Vec<char>>, i: usize, j: usize) -> u32 {
-    let digits_back: String = grid[i]
+fn find_whole_number(grid: &Schematic, p: Point) -> u32 {
+    let row = grid.row(p.y as usize);
+    let j = p.x as usize;
+    let start = row[..j]
         .iter()
-        .skip(j + 1)
-        .take_while(|c| c.is_numeric())
-        .cloned()
-        .collect::<String>()
-        .chars()
-        .collect();
-    let digits_front: String = grid[i]
+        .rposition(|c| !c.is_numeric())
+        .map_or(0, |i| i + 1);
+    let end = row[j..]
         .iter()
-        .rev()
-        .skip(grid[i].len() - j - 1)
-        .take_while(|c| c.is_numeric())
-        .cloned()
-        .collect::<String>()
-        .chars()
-        .rev()
-        .collect();
-    (digits_front + digits_back.as_str()).parse().unwrap()
+        .position(|c| !c.is_numeric())
+        .map_or(row.len(), |i| i + j);
+    row[start..end].iter().collect::<String>().parse().unwrap()
 }
 
 pub fn p2(input: &String) -> String {
-    let grid: Vec<Vec<char>> = input.lines().map(|v| v.chars().collect()).collect();
+    let grid = Grid::parse(input, |c| c);
     let mut result = 0;
 
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            if let Some(parts) = find_gear_adj_parts(&grid, i, j) {
-                if parts.len() == 2 {
-                    result += parts.iter().fold(1, |acc, v| acc * v);
-                }
+    for p in grid.points() {
+        if let Some(parts) = find_gear_adj_parts(&grid, p) {
+            if parts.len() == 2 {
+                result += parts.iter().product::<u32>();
             }
         }
     }
 
     result.to_string()
 }
+
+/// Every number linked to the symbols around it, numbers touching none are dashed.
+struct Adjacency<'a>(&'a Schematic);
+
+impl GraphExport for Adjacency<'_> {
+    fn to_dot(&self) -> DotGraph {
+        let grid = self.0;
+        let mut graph = DotGraph::undirected("schematic");
+        let id = |kind: &str, p: Point| format!("{} {},{}", kind, p.x, p.y);
+        for (p, &c) in grid.iter().filter(|(_, &c)| is_symbol(c)) {
+            graph.node(&id("symbol", p), &[("label", &c.to_string()), ("shape", "box")]);
+        }
+        for p in grid.points() {
+            let starts_number = grid[p].is_numeric()
+                && !grid.get(p + Direction::Left).is_some_and(|c| c.is_numeric());
+            if !starts_number {
+                continue;
+            }
+            let (number, len) = parse_number(grid, p);
+            let symbols = (0..len as i64)
+                .flat_map(|i| find_adjs_by(grid, p + Direction::Right.offset() * i, is_symbol))
+                .collect::<BTreeSet<_>>();
+            let style = if symbols.is_empty() { "dashed" } else { "solid" };
+            graph.node(&id("number", p), &[("label", &number.to_string()), ("style", style)]);
+            for symbol in symbols {
+                graph.edge(&id("number", p), &id("symbol", symbol), &[]);
+            }
+        }
+        graph
+    }
+}
+
+pub fn dot(input: &str) -> String {
+    Adjacency(&Grid::parse(input, |c| c)).to_dot().to_string()
+}
@@ -1,36 +1,13 @@
-use itertools::Itertools;
+use crate::parse::{lines, number, numbers, parse_all, preceded, spaces, tag, Parser};
 
 type Card = (Vec<u32>, Vec<u32>);
 
 fn parse_input(input: &String) -> Vec<Card> {
-    input
-        .lines()
-        .into_iter()
-        .map(|line|
-            line
-                .chars()
-                .skip_while(|c| *c != ':')
-                .skip(1)
-                .collect::<String>()
-        )
-        .map(|v|
-            v
-                .split("|")
-                .map(|v|
-                    v
-                        .split_whitespace()
-                        .map(|v|
-                            v
-                                .trim()
-                                .parse::<u32>()
-                                .expect(format!("couldn't parse u32 value from = ({})", v).as_str())
-                        )
-                        .collect::<Vec<u32>>()
-                )
-                .collect_tuple()
-                .unwrap()
-        )
-        .collect::<Vec<Card>>()
+    let card = preceded(tag("Card").skip(spaces()).and(number::<u32>()).skip(tag(":")), numbers())
+        .skip(spaces())
+        .skip(tag("|"))
+        .and(numbers());
+    parse_all(lines(card), input).unwrap_or_else(|e| panic!("{}", e))
 }
 
 pub fn p1(input: &String) -> String {
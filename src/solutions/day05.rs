@@ -1,90 +1,37 @@
-use itertools::Itertools;
+use crate::interval::{Interval, PiecewiseMap, RangeSet};
+use crate::parallel;
+use crate::parse::{
+    blank_line, blocks, lines, number, numbers, parse_all, section, spaces, tag, take_while1, Parser,
+};
 
-fn parse_input(input: &String) -> (Vec<usize>, Mappings) {
-    (
-        input
-            .lines()
-            .find_or_first(|_| true)
-            .unwrap()
-            .chars()
-            .skip_while(|&c| c != ':')
-            .skip(1)
-            .collect::<String>()
-            .split_whitespace()
-            .map(|c| c.parse::<usize>().unwrap())
-            .collect::<Vec<usize>>(),
-        input
-            .split("\n\n")
-            .skip(1)
-            .map(
-                |data| match data.lines().collect::<Vec<&str>>().as_slice() {
-                    [_heading, tail @ ..] => tail
-                        .iter()
-                        .map(|line| {
-                            let (destination, source, range) = line
-                                .split(" ")
-                                .map(|v| v.parse::<usize>().unwrap())
-                                .collect_tuple()
-                                .unwrap();
-                            Mapping::new_with(source, destination, range)
-                        })
-                        .collect::<Vec<Mapping>>(),
-                    _ => unreachable!(),
-                },
-            )
-            .collect::<Vec<Vec<Mapping>>>(),
-    )
-}
-
-#[derive(Debug)]
-struct Mapping {
-    from: std::ops::Range<usize>,
-    to: std::ops::Range<usize>,
-}
-
-impl Mapping {
-    fn new_with(fstart: usize, dstart: usize, len: usize) -> Self {
-        Mapping {
-            from: fstart..fstart + len,
-            to: dstart..dstart + len,
+fn parse_input(input: &String) -> (Vec<i64>, Mappings) {
+    let seeds = section(tag("seeds"), numbers());
+    let mapping = number::<i64>()
+        .skip(spaces())
+        .and(number::<i64>())
+        .skip(spaces())
+        .and(number::<i64>())
+        .map(|((destination, source), len)| (Interval::with_len(source, len), destination - source));
+    let conversion = lines(mapping).map(|ranges| {
+        let mut map = PiecewiseMap::new();
+        for (source, offset) in ranges {
+            map.insert(source, offset);
         }
-    }
-
-    fn map(&self, n: usize) -> Option<usize> {
-        if self.from.contains(&n) {
-            Some(
-                self.to
-                    .clone()
-                    .skip(n - self.from.start)
-                    .find_or_first(|_| true)
-                    .unwrap(),
-            )
-        } else {
-            None
-        }
-    }
+        map
+    });
+    let heading = take_while1("a map name", |c| c != ':' && c != '\n');
+    let maps = blocks(section(heading, conversion));
+    parse_all(seeds.skip(blank_line()).and(maps), input).unwrap_or_else(|e| panic!("{}", e))
 }
 
-type TypeConversions = Vec<Mapping>;
-type Mappings = Vec<TypeConversions>;
-fn apply_maps(ms: &Mappings, n: usize) -> usize {
-    let mut result = n;
-    for tc in ms {
-        for t in tc {
-            if let Some(r) = t.map(result) {
-                result = r;
-                break;
-            }
-        }
-    }
-    result
-}
+/// One map per category, seed to soil first and humidity to location last.
+type Mappings = Vec<PiecewiseMap>;
 
 pub fn p1(input: &String) -> String {
     let (seeds, mappings) = parse_input(input);
     seeds
         .iter()
-        .map(|&s| apply_maps(&mappings, s))
+        .map(|&s| mappings.iter().fold(s, |n, m| m.apply(n)))
         .min()
         .unwrap()
         .to_string()
@@ -92,10 +39,18 @@ pub fn p1(input: &String) -> String {
 
 pub fn p2(input: &String) -> String {
     let (seeds, mappings) = parse_input(input);
-    seeds
+    let seeds = seeds
         .chunks(2)
-        .map(|chunk| (chunk[0]..chunk[1] + chunk[0]))
-        .map(|s| s.map(|s| apply_maps(&mappings, s)).min().unwrap())
+        .map(|chunk| Interval::with_len(chunk[0], chunk[1]))
+        .collect::<Vec<_>>();
+    // Empty seed ranges have no location, so they don't count.
+    let nearest = |&seeds: &Interval| {
+        let locations = mappings.iter().fold(RangeSet::from(seeds), |set, m| m.image(&set));
+        locations.min()
+    };
+    parallel::map(&seeds, nearest)
+        .into_iter()
+        .flatten()
         .min()
         .unwrap()
         .to_string()
@@ -1,42 +1,47 @@
 use itertools::Itertools;
 
+use crate::parse::{
+    digits, newline, numbers, parse_all, preceded, section, separated, spaces, tag, ParseError, Parser,
+};
+use crate::validate::Assumption;
+
+pub static ASSUMPTIONS: &[Assumption] = &[
+    Assumption {
+        description: "times and distances line up, one race per column",
+        check: races_line_up,
+    },
+    Assumption {
+        description: "kerned time and distance fit in usize and so does the best race",
+        check: kerned_race_fits,
+    },
+];
+
 fn parse_input_1(input: &String) -> Vec<(usize, usize)> {
-    let (t, d) = input
-        .lines()
-        .map(|v| {
-            v.chars()
-                .skip_while(|&c| c != ':')
-                .skip(1)
-                .collect::<String>()
-        })
-        .map(|s| {
-            s.split_whitespace()
-                .map(|v| v.parse::<usize>().unwrap())
-                .collect_vec()
-        })
-        .collect_tuple()
-        .unwrap();
+    let times = section(tag("Time"), numbers::<usize>());
+    let distances = section(tag("Distance"), numbers::<usize>());
+    let (t, d) = parse_all(times.skip(newline()).and(distances), input)
+        .unwrap_or_else(|e| panic!("{}", e));
     t.into_iter().zip(d).collect_vec()
 }
 
+/// The digits of a line read as one number, ignoring the spaces between them.
+fn kerned<'a>() -> impl Parser<'a, usize> {
+    preceded(spaces(), separated(digits(), spaces())).try_map(|parts| {
+        parts
+            .concat()
+            .parse::<usize>()
+            .map_err(|_| String::from("a kerned number that fits in usize"))
+    })
+}
+
+fn parse_kerned(input: &str) -> Result<(usize, usize), ParseError> {
+    let time = section(tag("Time"), kerned());
+    let distance = section(tag("Distance"), kerned());
+    parse_all(time.skip(newline()).and(distance), input)
+}
+
 fn parse_input_2(input: &String) -> (usize, usize) {
-    let (t, d) = input
-        .lines()
-        .map(|v| {
-            v.chars()
-                .skip_while(|&c| c != ':')
-                .skip(1)
-                .collect::<String>()
-        })
-        .map(|s| {
-            s.chars()
-                .filter(|&c| c != ' ')
-                .collect::<String>()
-                .parse::<usize>()
-        })
-        .collect_tuple()
-        .unwrap();
-    t.into_iter().zip(d).collect_vec().pop().unwrap()
+    parse_kerned(input).unwrap_or_else(|e| panic!("{}", e))
 }
 
 fn ways((t, d): (usize, usize)) -> usize {
@@ -49,6 +54,31 @@ fn ways((t, d): (usize, usize)) -> usize {
     })
 }
 
+fn races_line_up(input: &str) -> Result<(), String> {
+    let lines = input.lines().collect_vec();
+    let [time, distance] = lines[..] else {
+        return Err(format!("expected 2 lines, found {}", lines.len()));
+    };
+    let count = |line: &str, label: &'static str| {
+        parse_all(section(tag(label), numbers::<usize>()), line)
+            .map(|values| values.len())
+            .map_err(|e| e.to_string())
+    };
+    let (times, distances) = (count(time, "Time")?, count(distance, "Distance")?);
+    if times != distances {
+        return Err(format!("{} times but {} distances", times, distances));
+    }
+    Ok(())
+}
+
+fn kerned_race_fits(input: &str) -> Result<(), String> {
+    let (t, _) = parse_kerned(input).map_err(|e| e.to_string())?;
+    (t / 2)
+        .checked_mul(t - t / 2)
+        .map(|_| ())
+        .ok_or(format!("best race for time {} overflows", t))
+}
+
 pub fn p1(input: &String) -> String {
     parse_input_1(input)
         .iter()
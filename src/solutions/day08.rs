@@ -2,122 +2,258 @@ use std::collections::HashMap;
 
 use itertools::Itertools;
 
-fn gcd(mut n: usize, mut m: usize) -> usize {
-    while m != 0 {
-        if m < n {
-            let t = m;
-            m = n;
-            n = t;
-        }
-        m = m % n;
-    }
-    n
+use crate::{cycle, math};
+use crate::dot::{DotGraph, GraphExport};
+use crate::intern::{Interner, Symbol, SymbolMap};
+use crate::parse::{blank_line, lines, pair_assignment, parse_all, word, ParseError, Parser};
+use crate::validate::Assumption;
+
+pub static ASSUMPTIONS: &[Assumption] = &[
+    Assumption {
+        description: "directions only contain L and R",
+        check: directions_are_lr,
+    },
+    Assumption {
+        description: "every node that is pointed to is defined",
+        check: all_nodes_defined,
+    },
+    Assumption {
+        description: "each ghost's cycle starts at its first Z and hits a Z every first-hit distance",
+        check: ghost_cycles_start_at_first_z,
+    },
+];
+
+/// A node's name with the names of its left and right neighbours.
+type Node = (String, (String, String));
+
+fn parse_network(input: &str) -> Result<(String, Vec<Node>), ParseError> {
+    let node = pair_assignment(word(), word(), word()).map(|(source, (left, right))| {
+        (source.to_string(), (left.to_string(), right.to_string()))
+    });
+    let (directions, network) = parse_all(word().skip(blank_line()).and(lines(node)), input)?;
+    Ok((directions.to_string(), network))
 }
 
-fn parse_input(input: &String) -> (String, Vec<(String, (String, String))>) {
-    let input = input.replace("\r\n", "\n");
-    let (directions, network) = input.split("\n\n").collect_tuple().unwrap();
-    let network = network
-        .lines()
-        .map(|line| {
-            let binding = line.replace(" ", "");
-            let (source, ways) = binding
-                .split("=")
-                .map(|v| v.to_string())
-                .collect_tuple()
-                .unwrap();
-            let (left, right) = ways
-                .strip_prefix("(")
-                .unwrap()
-                .strip_suffix(")")
-                .unwrap()
-                .split(",")
-                .map(|v| v.to_string())
-                .collect_tuple()
-                .unwrap();
-            (source, (left, right))
-        })
-        .collect_vec();
-    (directions.to_string(), network)
+fn parse_input(input: &String) -> (String, Vec<Node>) {
+    parse_network(input).unwrap_or_else(|e| panic!("{}", e))
 }
 
-pub fn p1(input: &String) -> String {
-    let (directions, network) = parse_input(input);
-    let mut network_map = HashMap::<String, (String, String)>::new();
-    for (name, (left, right)) in network {
-        network_map.insert(name, (left, right));
-    }
+/// The network with node names interned, so walking it never touches a string.
+struct Network {
+    names: Interner,
+    links: SymbolMap<(Symbol, Symbol)>,
+}
 
-    let mut current = network_map[&String::from("AAA")].clone();
-    let mut steps = 0usize;
-    let mut cursor = 0usize;
+impl Network {
+    fn new(nodes: &[Node]) -> Self {
+        let mut names = Interner::new();
+        let links = nodes
+            .iter()
+            .map(|(name, (left, right))| {
+                let links = (names.intern(left), names.intern(right));
+                (names.intern(name), links)
+            })
+            .collect();
+        Network { names, links }
+    }
 
-    loop {
-        let new_key = if directions.chars().nth(cursor).unwrap() == 'L' {
-            current.0.clone()
+    fn next(&self, node: Symbol, direction: char) -> Symbol {
+        let (left, right) = self.links[node];
+        if direction == 'L' {
+            left
         } else {
-            current.1.clone()
-        };
-        cursor = (cursor + 1) % directions.len();
-        steps += 1;
-        if new_key == "ZZZ" {
-            break;
+            right
         }
-        current = network_map[&new_key].clone();
     }
 
-    steps.to_string()
+    fn ends_with(&self, node: Symbol, c: char) -> bool {
+        self.names.resolve(node).ends_with(c)
+    }
 }
 
-pub fn p2(input: &String) -> String {
-    let (directions, network) = parse_input(input);
-    let mut network_map = HashMap::<String, (String, String)>::new();
-    for (name, (left, right)) in network {
-        network_map.insert(name, (left, right));
+impl GraphExport for Network {
+    /// Start nodes green, end nodes red, one edge per direction.
+    fn to_dot(&self) -> DotGraph {
+        let mut graph = DotGraph::directed("network");
+        for (node, name) in self.names.symbols() {
+            match name.chars().last() {
+                Some('A') => graph.node(name, &[("color", "green"), ("style", "bold")]),
+                Some('Z') => graph.node(name, &[("color", "red"), ("style", "bold")]),
+                _ => graph.node(name, &[]),
+            };
+            let Some(&(left, right)) = self.links.get(node) else {
+                continue;
+            };
+            let (left, right) = (self.names.resolve(left), self.names.resolve(right));
+            if left == right {
+                graph.edge(name, left, &[("label", "LR")]);
+            } else {
+                graph.edge(name, left, &[("label", "L")]);
+                graph.edge(name, right, &[("label", "R")]);
+            }
+        }
+        graph
     }
+}
 
-    let starting_nodes = network_map
-        .keys()
-        .filter(|k| k.ends_with("A"))
-        .map(|s| s.to_string())
-        .collect_vec();
+pub fn dot(input: &str) -> String {
+    let (_, network) = parse_network(input).unwrap_or_else(|e| panic!("{}", e));
+    Network::new(&network).to_dot().to_string()
+}
+
+fn directions_are_lr(input: &str) -> Result<(), String> {
+    let (directions, _) = parse_network(input).map_err(|e| e.to_string())?;
+    match directions.chars().find(|&c| c != 'L' && c != 'R') {
+        Some(c) => Err(format!("found direction {:?}", c)),
+        None => Ok(()),
+    }
+}
+
+fn all_nodes_defined(input: &str) -> Result<(), String> {
+    let (_, network) = parse_network(input).map_err(|e| e.to_string())?;
+    let names = network.iter().map(|(name, _)| name).collect_vec();
+    let undefined = network
+        .iter()
+        .flat_map(|(name, (left, right))| [(name, left), (name, right)])
+        .find(|(_, to)| !names.contains(to));
+    match undefined {
+        Some((from, to)) => Err(format!("{} points to undefined node {}", from, to)),
+        None => Ok(()),
+    }
+}
 
-    let mut cycles = Vec::<Vec<usize>>::new();
+fn ghost_cycles_start_at_first_z(input: &str) -> Result<(), String> {
+    let (directions, network) = parse_network(input).map_err(|e| e.to_string())?;
+    let directions = directions.chars().collect_vec();
+    let network_map = network.iter().cloned().collect::<HashMap<String, (String, String)>>();
+    let limit = network.len() * directions.len();
+    let step = |node: &String, cursor: usize| {
+        let (left, right) = network_map
+            .get(node)
+            .ok_or(format!("node {} is not defined", node))?;
+        let next = if directions[cursor] == 'L' { left } else { right };
+        Ok::<_, String>((next.clone(), (cursor + 1) % directions.len()))
+    };
 
-    for k in 0..starting_nodes.len() {
-        let mut current = starting_nodes[k].clone();
-        let mut cycle = Vec::new();
-        let mut first_found: Option<String> = Option::None;
+    // The LCM of first-hit distances is only right if every ghost keeps hitting
+    // a Z exactly every `offset` steps after its first one, so walk the whole
+    // (node, cursor) cycle entered at the first Z and check every hit on it.
+    for start in network.iter().map(|(name, _)| name).filter(|n| n.ends_with('A')) {
+        let (mut node, mut cursor) = (start.clone(), 0);
+        let mut offset = 0;
+        while offset == 0 || !node.ends_with('Z') {
+            if offset == limit {
+                return Err(format!("ghost {} never reaches a Z node", start));
+            }
+            (node, cursor) = step(&node, cursor)?;
+            offset += 1;
+        }
 
+        let first_z = (node.clone(), cursor);
         let mut steps = 0;
-        let mut cursor = 0;
         loop {
-            while steps == 0 || !current.ends_with("Z") {
-                steps += 1;
-                current = if directions.chars().nth(cursor).unwrap() == 'L' {
-                    network_map[&current].0.clone()
-                } else {
-                    network_map[&current].1.clone()
-                };
-                cursor = (cursor + 1) % directions.len();
+            if steps == limit {
+                return Err(format!("ghost {} never comes back to its first Z", start));
             }
-
-            cycle.push(steps);
-            if first_found.is_some() && current == first_found.unwrap() {
+            (node, cursor) = step(&node, cursor)?;
+            steps += 1;
+            if node.ends_with('Z') && steps % offset != 0 {
+                return Err(format!(
+                    "ghost {} first reaches a Z after {} steps, then {} after {} more",
+                    start, offset, node, steps
+                ));
+            }
+            if (node.clone(), cursor) == first_z {
                 break;
-            } else {
-                first_found = Some(current.clone());
-                steps = 0;
             }
         }
+        if steps % offset != 0 {
+            return Err(format!(
+                "ghost {} first reaches a Z after {} steps but cycles every {}",
+                start, offset, steps
+            ));
+        }
+    }
+    Ok(())
+}
 
-        cycles.push(cycle);
+pub fn p1(input: &String) -> String {
+    let (directions, network) = parse_input(input);
+    let network = Network::new(&network);
+    let goal = network.names.get("ZZZ").expect("there is no ZZZ node");
+
+    let mut current = network.names.get("AAA").expect("there is no AAA node");
+    let mut steps = 0usize;
+    for direction in directions.chars().cycle() {
+        current = network.next(current, direction);
+        steps += 1;
+        if current == goal {
+            break;
+        }
     }
 
-    let lcm_between = cycles.iter().map(|v| *v.first().unwrap()).collect_vec();
-    let mut lcm = (lcm_between[0] * lcm_between[1]) / gcd(lcm_between[0], lcm_between[1]);
-    for k in lcm_between.iter().skip(2) {
-        lcm = (lcm * *k) / gcd(lcm, *k);
+    steps.to_string()
+}
+
+pub fn p2(input: &String) -> String {
+    let (directions, network) = parse_input(input);
+    let directions = directions.chars().collect_vec();
+    let network = Network::new(&network);
+
+    // A ghost's state is its node and where it is in the directions, the walk
+    // from there is fully determined so it ends up going round a cycle.
+    let step = |&(node, cursor): &(Symbol, usize)| {
+        (network.next(node, directions[cursor]), (cursor + 1) % directions.len())
+    };
+    let walk = |start| std::iter::successors(Some(start), |state| Some(step(state)));
+
+    let starts = network
+        .links
+        .iter()
+        .filter(|&(node, _)| network.ends_with(node, 'A'))
+        .map(|(node, _)| (node, 0))
+        .collect_vec();
+    let cycles = starts.iter().map(|start| cycle::brent(start, step)).collect_vec();
+
+    // Before every ghost is inside its cycle just walk them all together.
+    let tail = cycles.iter().map(|c| c.mu).max().expect("there are no ..A nodes");
+    let mut walks = starts.iter().map(|&start| walk(start)).collect_vec();
+    for steps in 0..tail {
+        let nodes = walks.iter_mut().map(|w| w.next().unwrap().0).collect_vec();
+        if nodes.iter().all(|&n| network.ends_with(n, 'Z')) {
+            return steps.to_string();
+        }
     }
-    lcm.to_string()
+
+    // After that each ghost is on a Z at `offset + n * lambda` steps for every
+    // Z offset on its cycle, so the answer is the smallest CRT solution over
+    // one offset per ghost that is past all the tails.
+    let z_offsets = starts
+        .iter()
+        .zip(&cycles)
+        .map(|(&start, c)| {
+            walk(start)
+                .enumerate()
+                .skip(c.mu)
+                .take(c.lambda)
+                .filter(|&(_, (node, _))| network.ends_with(node, 'Z'))
+                .map(|(offset, _)| (offset as i128, c.lambda as i128))
+                .collect_vec()
+        })
+        .collect_vec();
+    let tail = tail as i128;
+    z_offsets
+        .into_iter()
+        .multi_cartesian_product()
+        .filter_map(|congruences| math::crt(&congruences).ok())
+        .map(|(steps, period)| {
+            if steps < tail {
+                steps + (tail - steps + period - 1) / period * period
+            } else {
+                steps
+            }
+        })
+        .min()
+        .expect("ghosts never line up")
+        .to_string()
 }
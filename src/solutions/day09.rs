@@ -1,32 +1,36 @@
 use itertools::Itertools;
 
-fn parse_input(input: &String) -> Vec<Vec<i32>> {
+use crate::bignum::BigInt;
+
+fn parse_input(input: &String) -> Vec<Vec<BigInt>> {
     input
         .lines()
         .map(|line| {
             line.split_whitespace()
-                .map(|v| v.parse::<i32>().unwrap())
+                .map(|v| v.parse::<BigInt>().unwrap())
                 .collect_vec()
         })
         .collect_vec()
 }
 
-fn find_gen_sum(histories: Vec<Vec<i32>>) -> i32 {
+/// Extrapolated next values summed, exactly since the differences of long
+/// histories can outgrow any primitive integer.
+fn find_gen_sum(histories: Vec<Vec<BigInt>>) -> BigInt {
     let mut generated = Vec::new();
     for history in histories {
-        let mut results: Vec<Vec<i32>> = vec![history.clone()];
-        while !results.last().unwrap().iter().all(|&v| v == 0) {
+        let mut results: Vec<Vec<BigInt>> = vec![history];
+        while !results.last().unwrap().iter().all(BigInt::is_zero) {
             let last = results.last().unwrap();
-            let result = (1..last.len()).map(|k| last[k] - last[k - 1]).collect_vec();
+            let result = (1..last.len()).map(|k| &last[k] - &last[k - 1]).collect_vec();
             results.push(result);
         }
-        let mut history_gen: i32 = 0;
+        let mut history_gen = BigInt::zero();
         for r in results.iter().rev() {
             history_gen = r.last().unwrap() + history_gen
         }
         generated.push(history_gen);
     }
-    generated.iter().sum::<i32>()
+    generated.into_iter().sum::<BigInt>()
 }
 
 pub fn p1(input: &String) -> String {
@@ -1,7 +1,25 @@
-use itertools::Itertools;
-use std::collections::VecDeque;
+use crate::aocspan;
+use crate::dot::{DotGraph, GraphExport};
+use crate::geometry;
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+use crate::search;
+use crate::validate::Assumption;
+use std::collections::HashMap;
+use std::fmt::Display;
 
-type Map = Vec<Vec<Pipe>>;
+pub static ASSUMPTIONS: &[Assumption] = &[
+    Assumption {
+        description: "there is exactly one S",
+        check: single_start,
+    },
+    Assumption {
+        description: "S has exactly two connecting pipes",
+        check: start_has_two_pipes,
+    },
+];
+
+type Map = Grid<Pipe>;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 enum Pipe {
@@ -16,316 +34,260 @@ enum Pipe {
     Empty,
 }
 
-#[derive(Clone, Copy)]
-enum Dir {
-    Top,
-    Bottom,
-    Left,
-    Right,
-}
-
-fn create_2d_vector<T: Clone>(rows: usize, cols: usize, default_value: T) -> Vec<Vec<T>> {
-    vec![vec![default_value; cols]; rows]
+impl Display for Pipe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Pipe::NorthToSouth => '|',
+            Pipe::EastToWest => '-',
+            Pipe::NorthToWest => 'J',
+            Pipe::NorthToEast => 'L',
+            Pipe::SouthToWest => '7',
+            Pipe::SouthToEast => 'F',
+            Pipe::PipeStart => 'S',
+            Pipe::NoPipe => '.',
+            Pipe::Empty => ' ',
+        };
+        write!(f, "{}", c)
+    }
 }
 
-fn allow_from_direction(direction: Dir) -> [Pipe; 3] {
+/// Pipes with an opening on their `side`.
+fn open_towards(side: Direction) -> [Pipe; 3] {
     use Pipe::*;
-    match direction {
-        Dir::Top => [NorthToSouth, NorthToEast, NorthToWest],
-        Dir::Bottom => [NorthToSouth, SouthToEast, SouthToWest],
-        Dir::Left => [EastToWest, NorthToWest, SouthToWest],
-        Dir::Right => [EastToWest, NorthToEast, SouthToEast],
+    match side {
+        Direction::Up => [NorthToSouth, NorthToEast, NorthToWest],
+        Direction::Down => [NorthToSouth, SouthToEast, SouthToWest],
+        Direction::Left => [EastToWest, NorthToWest, SouthToWest],
+        Direction::Right => [EastToWest, NorthToEast, SouthToEast],
     }
 }
 
+fn parse_map(input: &str) -> Result<Map, String> {
+    Grid::try_parse(input, |c| match c {
+        '|' => Pipe::NorthToSouth,
+        '-' => Pipe::EastToWest,
+        'J' => Pipe::NorthToWest,
+        'L' => Pipe::NorthToEast,
+        '7' => Pipe::SouthToWest,
+        'F' => Pipe::SouthToEast,
+        'S' => Pipe::PipeStart,
+        '.' => Pipe::NoPipe,
+        _ => Pipe::Empty,
+    })
+}
+
 fn parse_input(input: &String) -> Map {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| match c {
-                    '|' => Pipe::NorthToSouth,
-                    '-' => Pipe::EastToWest,
-                    'J' => Pipe::NorthToWest,
-                    'L' => Pipe::NorthToEast,
-                    '7' => Pipe::SouthToWest,
-                    'F' => Pipe::SouthToEast,
-                    'S' => Pipe::PipeStart,
-                    '.' => Pipe::NoPipe,
-                    _ => Pipe::Empty,
-                })
-                .collect_vec()
-        })
-        .collect_vec()
+    parse_map(input).unwrap_or_else(|e| panic!("{}", e))
 }
 
 #[allow(unused)]
 fn print_map(m: &Map) {
-    for r in m {
-        for c in r {
-            print!(
-                "{}",
-                match c {
-                    Pipe::NorthToSouth => '|',
-                    Pipe::EastToWest => '-',
-                    Pipe::NorthToWest => 'J',
-                    Pipe::NorthToEast => 'L',
-                    Pipe::SouthToWest => '7',
-                    Pipe::SouthToEast => 'F',
-                    Pipe::PipeStart => 'S',
-                    Pipe::NoPipe => '.',
-                    Pipe::Empty => ' ',
-                }
-            )
-        }
-        println!()
-    }
+    println!("{}", m);
 }
 
-fn traverse_map(pos: (usize, usize), map: &Map) -> Vec<Vec<usize>> {
-    let mut q = VecDeque::<(usize, usize)>::new();
-    let mut ds = create_2d_vector(map.len(), map[0].len(), 0usize);
-    q.push_back(pos);
-
-    while q.len() > 0 {
-        let (ii, jj) = q.pop_front().unwrap();
+/// Steps from `pos` to every tile of the loop, following pipes that open towards each other.
+/// S has to be replaced by its fitting pipe first.
+fn traverse_map(pos: Point, map: &Map) -> HashMap<Point, usize> {
+    search::bfs_distances([pos], |&current| {
+        [Direction::Down, Direction::Right, Direction::Left, Direction::Up]
+            .into_iter()
+            .filter(move |&direction| {
+                open_towards(direction).contains(&map[current])
+                    && map
+                        .get(current + direction)
+                        .is_some_and(|pipe| open_towards(direction.opposite()).contains(pipe))
+            })
+            .map(move |direction| current + direction)
+    })
+}
 
-        for ((i, j), direction) in vec![
-            ((1, 0), Dir::Top),
-            ((0, 1), Dir::Left),
-            ((0, -1), Dir::Right),
-            ((-1, 0), Dir::Bottom),
-        ] {
-            let (i, j) = ((ii as i64) + i, (jj as i64) + j);
-            if i < 0 || j < 0 || i >= (map.len() as i64) || j >= (map[0].len() as i64) {
-                continue;
-            }
-            let (i, j) = (i as usize, j as usize);
-            if ds[i][j] > 0 {
-                continue;
-            }
+fn find_start_position(map: &Map) -> Point {
+    map.find(|&tile| tile == Pipe::PipeStart).unwrap()
+}
 
-            if allow_from_direction(direction).contains(&map[i][j]) {
-                ds[i][j] = ds[ii][jj] + 1;
-                q.push_back((i, j));
-            }
-        }
+fn single_start(input: &str) -> Result<(), String> {
+    match input.chars().filter(|&c| c == 'S').count() {
+        1 => Ok(()),
+        n => Err(format!("found {} start tiles", n)),
     }
-
-    ds
 }
 
-fn find_start_position(map: &Map) -> (usize, usize) {
-    map.iter()
-        .enumerate()
-        .find_map(|(i, row)| {
-            if let Some(j) = row.iter().enumerate().find_map(|(i, tile)| match tile {
-                Pipe::PipeStart => Some(i),
-                _ => None,
-            }) {
-                Some((i, j))
-            } else {
-                None
-            }
-        })
-        .unwrap()
+fn start_has_two_pipes(input: &str) -> Result<(), String> {
+    if !input.contains('S') {
+        return Err(String::from("there is no S"));
+    }
+    let map = parse_map(input)?;
+    let start_position = find_start_position(&map);
+    match connecting_directions(&map, start_position).len() {
+        2 => Ok(()),
+        n => Err(format!("S at {:?} connects to {} pipes", start_position, n)),
+    }
 }
 
 pub fn p1(input: &String) -> String {
-    let map = parse_input(input);
+    let mut map = {
+        let _span = aocspan!("parse");
+        parse_input(input)
+    };
     let start_position = find_start_position(&map);
-    let distances = traverse_map(start_position, &map);
-    distances
-        .into_iter()
-        .flat_map(|row| row.into_iter())
-        .max()
-        .unwrap()
-        .to_string()
+    map[start_position] = fitting_pipe(&map, start_position);
+    let distances = {
+        let _span = aocspan!("traverse");
+        traverse_map(start_position, &map)
+    };
+    distances.values().max().unwrap().to_string()
 }
 
-fn fitting_pipe(map: &Map, (ii, jj): (usize, usize)) -> Pipe {
+/// Directions out of `p` whose neighbouring pipe connects back to it.
+fn connecting_directions(map: &Map, p: Point) -> Vec<Direction> {
     let mut dirs = Vec::new();
-    for ((i, j), from_direction, opposite) in vec![
-        ((1, 0), Dir::Top, Dir::Bottom),
-        ((0, 1), Dir::Left, Dir::Right),
-        ((0, -1), Dir::Right, Dir::Left),
-        ((-1, 0), Dir::Bottom, Dir::Top),
-    ] {
-        let (i, j) = ((ii as i64) + i, (jj as i64) + j);
-        if i < 0 || j < 0 || i >= (map.len() as i64) || j >= (map[0].len() as i64) {
+    for direction in [Direction::Down, Direction::Right, Direction::Left, Direction::Up] {
+        let Some(pipe) = map.get(p + direction) else {
             continue;
-        }
-        let (i, j) = (i as usize, j as usize);
-        if allow_from_direction(from_direction).contains(&map[i][j]) {
-            dirs.push(opposite);
+        };
+        if open_towards(direction.opposite()).contains(pipe) {
+            dirs.push(direction);
         }
     }
+    dirs
+}
+
+fn fitting_pipe(map: &Map, p: Point) -> Pipe {
+    let dirs = connecting_directions(map, p);
     if dirs.len() == 2 {
-        if let Some(v) = allow_from_direction(dirs[0])
+        if let Some(v) = open_towards(dirs[0])
             .iter()
-            .find(|v| allow_from_direction(dirs[1]).contains(v))
+            .find(|v| open_towards(dirs[1]).contains(v))
         {
             return *v;
         }
     }
-    map[ii][jj]
+    map[p]
 }
 
-fn upscale_map_2(m: &Map) -> Map {
-    let mut new_map: Map = create_2d_vector(m.len() * 2 + 2, m[0].len() * 2 + 2, Pipe::NoPipe);
-    for i in 0..m.len() {
-        for j in 0..m[0].len() {
-            new_map[1 + (i * 2)][1 + (j * 2)] = m[i][j];
-        }
+/// Directions taken walking the main loop once, starting and ending at `start`.
+fn loop_directions(map: &Map, start: Point) -> Vec<Direction> {
+    let mut heading = connecting_directions(map, start)[0];
+    let mut position = start + heading;
+    let mut directions = vec![heading];
+    while position != start {
+        let came_from = heading.opposite();
+        heading = Direction::ALL
+            .into_iter()
+            .find(|&d| d != came_from && open_towards(d).contains(&map[position]))
+            .unwrap();
+        position += heading;
+        directions.push(heading);
     }
-    for i in 0..new_map.len() {
-        for j in 0..new_map[0].len() {
-            match new_map[i][j] {
-                Pipe::NoPipe => new_map[i][j] = fitting_pipe(&new_map, (i, j)),
-                _ => (),
-            }
-        }
-    }
-    new_map
-}
-
-fn downscale_map_2(m: &Map) -> Map {
-    let mut new_map = create_2d_vector((m.len() - 2) / 2, (m[0].len() - 2) / 2, Pipe::NoPipe);
-    for i in (1..m.len() - 1).step_by(2) {
-        for j in (1..m[i].len() - 1).step_by(2) {
-            let (ii, jj) = ((i - 1) / 2, (j - 1) / 2);
-            new_map[ii][jj] = m[i][j];
-        }
-    }
-    new_map
+    directions
 }
 
 pub fn p2(input: &String) -> String {
-    let mut map = parse_input(input);
+    let mut map = {
+        let _span = aocspan!("parse");
+        parse_input(input)
+    };
 
-    // Find and replace S with its fitting pipe and upscale the map by 2x.
+    // Find and replace S with its fitting pipe so the loop can be walked through it.
     let start_position = find_start_position(&map);
-    let start_pipe = fitting_pipe(&map, start_position);
-    map[start_position.0][start_position.1] = start_pipe;
-    let mut upscaled = upscale_map_2(&map);
+    map[start_position] = fitting_pipe(&map, start_position);
 
-    // Remove everything that is a Pipe::NoPipe via DFS.
-    // No need to keep track of visits,
-    // being != Pipe::NoPipe is considered a DFS visit
-    {
-        let mut stack = vec![(0, 0)];
-        while stack.len() > 0 {
-            let (ii, jj) = stack.pop().unwrap();
-            match upscaled[ii][jj] {
-                Pipe::NoPipe => (),
-                _ => continue,
-            }
+    // Tile centres along the loop form a lattice polygon, the enclosed tiles
+    // are exactly its interior lattice points.
+    let polygon = {
+        let _span = aocspan!("walk loop");
+        let steps = loop_directions(&map, start_position).into_iter().map(|d| (d, 1));
+        geometry::polygon_from_walk(start_position, steps)
+    };
+    geometry::interior_points(&polygon).to_string()
+}
 
-            upscaled[ii][jj] = Pipe::Empty;
+/// The main loop's tiles in walking order, starting from S.
+struct PipeLoop {
+    tiles: Vec<(Point, Pipe)>,
+}
 
-            for (i, j) in vec![(1, 0), (0, 1), (0, -1), (-1, 0)] {
-                let (i, j) = ((ii as i64) + i, (jj as i64) + j);
-                if i < 0 || j < 0 || i >= (upscaled.len() as i64) || j >= (upscaled[0].len() as i64)
-                {
-                    continue;
-                }
-                stack.push((i as usize, j as usize));
-            }
+impl GraphExport for PipeLoop {
+    /// Pinned to the tiles' map positions for `neato`, S is green.
+    fn to_dot(&self) -> DotGraph {
+        let mut graph = DotGraph::undirected("pipe loop");
+        graph.attribute("layout", "neato");
+        let id = |p: Point| format!("{},{}", p.x, p.y);
+        for (i, &(p, pipe)) in self.tiles.iter().enumerate() {
+            let position = format!("{},{}!", p.x, -p.y);
+            let color = if i == 0 { "green" } else { "black" };
+            graph.node(
+                &id(p),
+                &[("label", &pipe.to_string()), ("pos", &position), ("color", color), ("shape", "square")],
+            );
+            let (next, _) = self.tiles[(i + 1) % self.tiles.len()];
+            graph.edge(&id(p), &id(next), &[]);
         }
+        graph
     }
+}
 
-    // Remove outside excess map by checking the sorroundings of a cell.
-    {
-        for i in 1..upscaled.len() - 1 {
-            for j in 1..upscaled[i].len() - 1 {
-                let dirs = vec![
-                    (i, j - 1),
-                    (i, j + 1),
-                    (i + 1, j),
-                    (i - 1, j),
-                    (i - 1, j - 1),
-                    (i - 1, j + 1),
-                    (i + 1, j - 1),
-                    (i + 1, j + 1),
-                ];
-                match upscaled[i][j] {
-                    Pipe::NoPipe | Pipe::Empty => (),
-                    _ => {
-                        let mut has_any = false;
-                        for (ii, jj) in dirs {
-                            match upscaled[ii][jj] {
-                                Pipe::NoPipe => {
-                                    has_any = true;
-                                    break;
-                                }
-                                _ => (),
-                            }
-                        }
-                        if !has_any {
-                            upscaled[i][j] = Pipe::Empty;
-                        }
-                    }
-                }
-            }
-        }
-    }
+pub fn dot(input: &str) -> String {
+    let mut map = parse_map(input).unwrap_or_else(|e| panic!("{}", e));
+    let start_position = find_start_position(&map);
+    map[start_position] = fitting_pipe(&map, start_position);
+    let mut position = start_position;
+    let tiles = loop_directions(&map, start_position)
+        .into_iter()
+        .map(|direction| {
+            let tile = (position, map[position]);
+            position += direction;
+            tile
+        })
+        .collect();
+    PipeLoop { tiles }.to_dot().to_string()
+}
 
-    // Fix up any artifact from previous step.
-    {
-        for i in 0..upscaled.len() {
-            for j in 0..upscaled[i].len() {
-                match upscaled[i][j] {
-                    Pipe::Empty => upscaled[i][j] = fitting_pipe(&upscaled, (i, j)),
-                    _ => (),
-                }
-            }
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsu::label_grid;
 
-    // DFS visit empty cells and replace any first occurrence of a cell != Pipe::Empty
-    // and Pipe::NoPipe as it is the border of our pipes main loop.
-    // Removing the border will give us all that is contained inside of our main loop.
-    {
-        let mut stack = vec![(0, 0)];
-        let mut vs = create_2d_vector(upscaled.len(), upscaled[0].len(), false);
-        while stack.len() > 0 {
-            let (ii, jj) = stack.pop().unwrap();
-            if vs[ii][jj] {
-                continue;
-            }
-            vs[ii][jj] = true;
-            match upscaled[ii][jj] {
-                Pipe::NoPipe => continue,
-                _ => stack.push((ii, jj)),
-            }
+    #[test]
+    fn farthest_tile_ignores_pipes_next_to_the_loop() {
+        // The `-` right of the `J` opens towards it, but the `J` doesn't open back.
+        let input = String::from(".....\n.S-7.\n.|.|.\n.L-J-\n.....\n");
+        assert_eq!(single_start(&input), Ok(()));
+        assert_eq!(start_has_two_pipes(&input), Ok(()));
+        assert_eq!(p1(&input), "4");
+    }
 
-            upscaled[ii][jj] = Pipe::Empty;
+    #[test]
+    fn counts_regions_enclosed_by_the_loop() {
+        let map = "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+        let grid = Grid::parse(map, |c| c);
+        let components = label_grid(&grid, |_, &a, _, &b| a == '.' && b == '.');
 
-            for (i, j) in vec![(1, 0), (0, 1), (0, -1), (-1, 0)] {
-                let (i, j) = ((ii as i64) + i, (jj as i64) + j);
-                if i >= 0
-                    && j >= 0
-                    && i < (upscaled.len() as i64)
-                    && j < (upscaled[i as usize].len() as i64)
-                {
-                    stack.push((i as usize, j as usize));
-                }
+        // Pipes and ground reaching the edge aren't enclosed.
+        let mut open = vec![false; components.count()];
+        for (p, &label) in components.labels.iter() {
+            let on_edge = p.x == 0
+                || p.y == 0
+                || p.x as usize == grid.width() - 1
+                || p.y as usize == grid.height() - 1;
+            if grid[p] != '.' || on_edge {
+                open[label] = true;
             }
         }
+        let enclosed = (0..components.count())
+            .filter(|&label| !open[label])
+            .map(|label| components.sizes[label])
+            .collect::<Vec<_>>();
+        assert_eq!(enclosed, vec![2, 2]);
+        assert_eq!(p2(&String::from(map)), "4");
     }
-
-    // Downscale the map back to its original size, any cell != Pipe::Empty will be part of the
-    // area enclosed by the main loop.
-    let downscaled = downscale_map_2(&upscaled);
-    let count = downscaled
-        .iter()
-        .flat_map(|v| {
-            v.iter().map(|v| match v {
-                Pipe::Empty => 0,
-                _ => 1,
-            })
-        })
-        .sum::<usize>();
-
-    count.to_string()
 }
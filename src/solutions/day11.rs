@@ -1,16 +1,16 @@
 use itertools::Itertools;
 
-type Image = Vec<Vec<char>>;
+use crate::grid::Grid;
+use crate::point::Point;
+
+type Image = Grid<char>;
 fn parse_input(input: &String) -> Image {
-    input
-        .lines()
-        .map(|line| line.chars().collect_vec())
-        .collect_vec()
+    Grid::parse(input, |c| c)
 }
 
 fn find_empty_rows(image: &Image) -> Vec<usize> {
     image
-        .iter()
+        .rows()
         .enumerate()
         .filter(|(_, row)| row.iter().all(|&v| v == '.'))
         .map(|(i, _)| i)
@@ -18,63 +18,37 @@ fn find_empty_rows(image: &Image) -> Vec<usize> {
 }
 
 fn find_empty_cols(image: &Image) -> Vec<usize> {
-    (0..image[0].len())
-        .filter(|&j| image.iter().all(|v| v[j] == '.'))
+    (0..image.width())
+        .filter(|&j| image.column(j).all(|&v| v == '.'))
         .collect_vec()
 }
 
-fn find_galaxies_pos(image: &Image) -> Vec<(usize, usize)> {
+fn find_galaxies_pos(image: &Image) -> Vec<Point> {
     image
         .iter()
-        .enumerate()
-        .filter_map(|(i, v)| {
-            let result = v
-                .iter()
-                .enumerate()
-                .filter_map(|(j, &v)| if v == '#' { Some((i, j)) } else { None })
-                .collect_vec();
-            if result.len() > 0 {
-                Some(result)
-            } else {
-                None
-            }
-        })
-        .flat_map(|v| v.into_iter())
+        .filter(|(_, &v)| v == '#')
+        .map(|(p, _)| p)
         .collect_vec()
 }
 
-fn distance(p1: (usize, usize), p2: (usize, usize)) -> usize {
-    p1.0.abs_diff(p2.0) + p1.1.abs_diff(p2.1)
-}
-
-fn expanded_distances(image: &Image, expansion_index: usize) -> Vec<usize> {
-    let galaxies_pos = find_galaxies_pos(&image);
-    let empty_rows = find_empty_rows(&image);
-    let empty_cols = find_empty_cols(&image);
+fn expanded_distances(image: &Image, expansion_index: usize) -> Vec<u64> {
+    let galaxies_pos = find_galaxies_pos(image);
+    let empty_rows = find_empty_rows(image);
+    let empty_cols = find_empty_cols(image);
+    let growth = (expansion_index - 1) as i64;
     let galaxies_pos = galaxies_pos
         .iter()
-        .map(|v| {
-            let rbehind = empty_rows.iter().filter(|&&r| v.0 > r).count();
-            if rbehind > 0 {
-                (v.0 + (rbehind * (expansion_index - 1)), v.1)
-            } else {
-                *v
-            }
-        })
-        .map(|v| {
-            let cbehind = empty_cols.iter().filter(|&&c| v.1 > c).count();
-            if cbehind > 0 {
-                (v.0, v.1 + (cbehind * (expansion_index - 1)))
-            } else {
-                v
-            }
+        .map(|p| {
+            let rbehind = empty_rows.iter().filter(|&&r| p.y > r as i64).count() as i64;
+            let cbehind = empty_cols.iter().filter(|&&c| p.x > c as i64).count() as i64;
+            Point::new(p.x + cbehind * growth, p.y + rbehind * growth)
         })
         .collect_vec();
     galaxies_pos
         .iter()
         .enumerate()
         .flat_map(|(i, pos)| {
-            (i + 1..galaxies_pos.len()).map(|j| distance(pos.clone(), galaxies_pos[j]))
+            galaxies_pos[i + 1..].iter().map(|other| pos.manhattan(*other))
         })
         .collect_vec()
 }
@@ -82,11 +56,11 @@ fn expanded_distances(image: &Image, expansion_index: usize) -> Vec<usize> {
 pub fn p1(input: &String) -> String {
     let image = parse_input(input);
     let distances = expanded_distances(&image, 2);
-    distances.iter().sum::<usize>().to_string()
+    distances.iter().sum::<u64>().to_string()
 }
 
 pub fn p2(input: &String) -> String {
     let image = parse_input(input);
     let distances = expanded_distances(&image, 1000000);
-    distances.iter().sum::<usize>().to_string()
+    distances.iter().sum::<u64>().to_string()
 }
@@ -1,5 +1,7 @@
+use crate::aoccount;
+use crate::memo::Memo;
+use crate::parallel;
 use itertools::Itertools;
-use std::collections::HashMap;
 
 fn parse_input(input: &String) -> Vec<(Vec<char>, Vec<usize>)> {
     input
@@ -68,66 +70,45 @@ pub fn _brute_force_p1(input: &String) -> String {
         .to_string()
 }
 
-fn recurse_pattern(
-    mut pattern: Vec<char>,
-    mut springs: Vec<usize>,
-    pos: usize,
-    running: bool,
-    memo: &mut HashMap<(Vec<char>, Vec<usize>, usize, bool), usize>,
-) -> usize {
-    let key = (
-        pattern.iter().skip(pos).cloned().collect(),
-        springs.clone(),
-        pos,
-        running,
-    );
-    if let Some(&result) = memo.get(&key) {
-        return result;
-    }
+/// Where the count is at: next position in the pattern, index of the
+/// current group of damaged springs, how many of it are still to be placed
+/// and whether the previous spring was part of it.
+type State = (usize, usize, usize, bool);
+
+fn count_arrangements(pattern: &[char], springs: &[usize]) -> usize {
+    let group_size = |group: usize| springs.get(group).copied().unwrap_or(0);
+
+    // Counts arrangements from `state` with the spring at `pos` being `c`.
+    let place = |recurse: &mut dyn FnMut(State) -> usize, (pos, group, left, running): State, c: char| {
+        match c {
+            '#' if group == springs.len() || left == 0 => 0,
+            '#' => recurse((pos + 1, group, left - 1, left > 1)),
+            '.' if group < springs.len() && left == 0 => {
+                recurse((pos + 1, group + 1, group_size(group + 1), false))
+            }
+            '.' if group < springs.len() && running => 0,
+            '.' => recurse((pos + 1, group, left, false)),
+            _ => unreachable!(),
+        }
+    };
 
-    let result =
-        if springs.is_empty() && pattern.iter().skip(pos).filter(|c| **c == '#').count() == 0 {
+    let mut memo = Memo::new();
+    let total = memo.call((0, 0, group_size(0), false), &|recurse, state| {
+        let (pos, group, _, running) = state;
+        if group == springs.len() && !pattern[pos.min(pattern.len())..].contains(&'#') {
             1
         } else if pos >= pattern.len() {
             0
+        } else if pattern[pos] == '?' {
+            let operational = if running { 0 } else { place(recurse, state, '.') };
+            operational + place(recurse, state, '#')
         } else {
-            match pattern[pos] {
-                '?' => {
-                    let mut r1 = 0;
-                    if !running {
-                        pattern[pos] = '.';
-                        r1 = recurse_pattern(pattern.clone(), springs.clone(), pos, running, memo);
-                    }
-
-                    pattern[pos] = '#';
-                    let r2 = recurse_pattern(pattern.clone(), springs.clone(), pos, running, memo);
-                    r1 + r2
-                }
-                '#' => {
-                    if springs.is_empty() || springs[0] == 0 {
-                        0
-                    } else {
-                        springs[0] -= 1;
-                        let running = springs[0] > 0;
-                        recurse_pattern(pattern.clone(), springs.clone(), pos + 1, running, memo)
-                    }
-                }
-                '.' => {
-                    if !springs.is_empty() && springs[0] == 0 {
-                        springs.remove(0);
-                        recurse_pattern(pattern.clone(), springs.clone(), pos + 1, false, memo)
-                    } else if !springs.is_empty() && springs[0] > 0 && running {
-                        0
-                    } else {
-                        recurse_pattern(pattern.clone(), springs.clone(), pos + 1, false, memo)
-                    }
-                }
-                _ => unreachable!(),
-            }
-        };
-
-    memo.insert(key, result);
-    result
+            place(recurse, state, pattern[pos])
+        }
+    });
+    aoccount!("memo hits", memo.stats().hits);
+    aoccount!("memo misses", memo.stats().misses);
+    total
 }
 
 fn replace_contiguous_duplicates<T: PartialEq + Clone>(vec: Vec<T>, value: T) -> Vec<T> {
@@ -169,11 +150,7 @@ fn solve(input: &String, repeat_times: usize) -> usize {
             result
         })
         .collect_vec();
-    let mut memo = HashMap::new();
-    input_data
-        .into_iter()
-        .map(|(p, s)| recurse_pattern(p, s, 0, false, &mut memo))
-        .sum::<usize>()
+    parallel::map_reduce(&input_data, |(p, s)| count_arrangements(p, s), 0, |sum, n| sum + n)
 }
 
 pub fn p1(input: &String) -> String {
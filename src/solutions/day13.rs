@@ -1,68 +1,65 @@
 use itertools::Itertools;
 
-type Pattern = Vec<Vec<char>>;
-fn parse_input(input: &String) -> Vec<Pattern> {
+use crate::grid::Grid;
+use crate::validate::Assumption;
+use crate::view::{Line, View};
+
+pub static ASSUMPTIONS: &[Assumption] = &[
+    Assumption {
+        description: "every pattern has a perfect reflection",
+        check: every_pattern_reflects,
+    },
+    Assumption {
+        description: "every pattern has a reflection with exactly one smudge",
+        check: every_pattern_reflects_with_smudge,
+    },
+];
+
+type Pattern = Grid<char>;
+fn parse_patterns(input: &str) -> Result<Vec<Pattern>, String> {
     input
         .split("\n\n")
-        .map(|pattern_str| {
-            pattern_str
-                .lines()
-                .map(|v| v.chars().collect_vec())
-                .collect_vec()
+        .enumerate()
+        .map(|(i, pattern_str)| {
+            Grid::try_parse(pattern_str, |c| c).map_err(|e| format!("pattern {}: {}", i + 1, e))
         })
-        .collect_vec()
+        .collect()
 }
 
-#[allow(unused)]
-fn print_pattern(pattern: &Pattern) {
-    let pattern_str = pattern
-        .iter()
-        .map(|v| v.iter().collect::<String>())
-        .join("\n");
-    println!("pattern =\n{}", pattern_str);
+fn parse_input(input: &String) -> Vec<Pattern> {
+    parse_patterns(input).unwrap_or_else(|e| panic!("{}", e))
 }
 
-fn vecs_match<T>(v1: &Vec<T>, v2: &Vec<T>) -> bool
-where
-    T: PartialEq,
-{
-    v1.iter().zip(v2.iter()).all(|(v1, v2)| *v1 == *v2)
+#[allow(unused)]
+fn print_pattern(pattern: &Pattern) {
+    println!("pattern =\n{}", pattern);
 }
 
-fn vecs_diff<T>(v1: &Vec<T>, v2: &Vec<T>) -> Vec<bool>
+fn vecs_match<T>(v1: impl Iterator<Item = T>, v2: impl Iterator<Item = T>) -> bool
 where
     T: PartialEq,
 {
-    v1.iter()
-        .zip(v2.iter())
-        .map(|(v1, v2)| *v1 != *v2)
-        .collect_vec()
+    v1.zip(v2).all(|(v1, v2)| v1 == v2)
 }
 
-fn vecs_similar<T>(v1: &Vec<T>, v2: &Vec<T>) -> bool
+fn vecs_diff<T>(v1: impl Iterator<Item = T>, v2: impl Iterator<Item = T>) -> Vec<bool>
 where
     T: PartialEq,
 {
-    vecs_match(v1, v2) || vecs_diff(v1, v2).iter().filter(|v| **v == true).count() == 1
+    v1.zip(v2).map(|(v1, v2)| v1 != v2).collect_vec()
 }
 
-fn transpose<T>(v: &Vec<Vec<T>>) -> Vec<Vec<T>>
-where
-    T: Clone,
-{
-    assert!(!v.is_empty());
-    (0..v[0].len())
-        .map(|i| v.iter().map(|inner| inner[i].clone()).collect::<Vec<T>>())
-        .collect()
+fn vecs_similar(v1: Line<char>, v2: Line<char>) -> bool {
+    vecs_diff(v1, v2).iter().filter(|v| **v).count() <= 1
 }
 
 fn _find_exact_reflection_row_by(
-    pattern: &Pattern,
-    comp: fn(&Vec<char>, &Vec<char>) -> bool,
+    pattern: View<char>,
+    comp: fn(Line<char>, Line<char>) -> bool,
 ) -> Vec<usize> {
     let mut result = Vec::new();
-    for i in 0..pattern.len() - 1 {
-        if comp(&pattern[i], &pattern[i + 1]) {
+    for i in 0..pattern.height() - 1 {
+        if comp(pattern.row(i), pattern.row(i + 1)) {
             result.push(i);
         }
     }
@@ -71,22 +68,21 @@ fn _find_exact_reflection_row_by(
 }
 
 fn find_exact_reflections_row(pattern: &Pattern) -> Vec<usize> {
-    _find_exact_reflection_row_by(&pattern, |v1, v2| vecs_match(v1, v2))
+    _find_exact_reflection_row_by(pattern.view(), |v1, v2| vecs_match(v1, v2))
 }
 
 fn find_exact_reflections_col(pattern: &Pattern) -> Vec<usize> {
-    let transposed_pattern = transpose(pattern);
-    _find_exact_reflection_row_by(&transposed_pattern, |v1, v2| vecs_match(v1, v2))
+    _find_exact_reflection_row_by(pattern.view().transposed(), |v1, v2| vecs_match(v1, v2))
 }
 
-fn _is_complete_row_reflection_from(pattern: &Pattern, i: usize) -> bool {
+fn _is_complete_row_reflection_from(pattern: View<char>, i: usize) -> bool {
     let (mut i, mut j) = (i, i + 1);
     loop {
-        if !vecs_match(&pattern[i], &pattern[j]) {
+        if !vecs_match(pattern.row(i), pattern.row(j)) {
             break false;
         }
 
-        if i == 0 || j == pattern.len() - 1 {
+        if i == 0 || j == pattern.height() - 1 {
             break true;
         }
 
@@ -96,38 +92,36 @@ fn _is_complete_row_reflection_from(pattern: &Pattern, i: usize) -> bool {
 }
 
 fn is_complete_row_reflection_from(pattern: &Pattern, i: usize) -> bool {
-    _is_complete_row_reflection_from(pattern, i)
+    _is_complete_row_reflection_from(pattern.view(), i)
 }
 
 fn is_complete_col_reflection_from(pattern: &Pattern, i: usize) -> bool {
-    let transposed_pattern = transpose(pattern);
-    _is_complete_row_reflection_from(&transposed_pattern, i)
+    _is_complete_row_reflection_from(pattern.view().transposed(), i)
 }
 
 fn find_almost_exact_reflections_row(pattern: &Pattern) -> Vec<usize> {
-    _find_exact_reflection_row_by(&pattern, |v1, v2| vecs_similar(v1, v2))
+    _find_exact_reflection_row_by(pattern.view(), vecs_similar)
 }
 
 fn find_almost_exact_reflections_col(pattern: &Pattern) -> Vec<usize> {
-    let transposed_pattern = transpose(pattern);
-    _find_exact_reflection_row_by(&transposed_pattern, |v1, v2| vecs_similar(v1, v2))
+    _find_exact_reflection_row_by(pattern.view().transposed(), vecs_similar)
 }
 
-fn _is_almost_complete_row_reflection_from(pattern: &Pattern, i: usize) -> bool {
+fn _is_almost_complete_row_reflection_from(pattern: View<char>, i: usize) -> bool {
     let (mut i, mut j) = (i, i + 1);
     let mut n_smudges = 0;
 
     loop {
-        let diffs = vecs_diff(&pattern[i], &pattern[j])
+        let diffs = vecs_diff(pattern.row(i), pattern.row(j))
             .iter()
-            .filter(|v| **v == true)
+            .filter(|v| **v)
             .count();
         n_smudges += if diffs == 1 { 1 } else { 0 };
         if diffs > 1 {
             break false;
         }
 
-        if i == 0 || j == pattern.len() - 1 {
+        if i == 0 || j == pattern.height() - 1 {
             break n_smudges == 1;
         }
 
@@ -137,65 +131,77 @@ fn _is_almost_complete_row_reflection_from(pattern: &Pattern, i: usize) -> bool
 }
 
 fn is_almost_complete_row_reflection_from(pattern: &Pattern, i: usize) -> bool {
-    _is_almost_complete_row_reflection_from(pattern, i)
+    _is_almost_complete_row_reflection_from(pattern.view(), i)
 }
 
 fn is_almost_complete_col_reflection_from(pattern: &Pattern, i: usize) -> bool {
-    let transposed_pattern = transpose(pattern);
-    _is_almost_complete_row_reflection_from(&transposed_pattern, i)
+    _is_almost_complete_row_reflection_from(pattern.view().transposed(), i)
 }
 
-pub fn p1(input: &String) -> String {
-    let patterns = parse_input(input);
-    let mut score = 0;
-    for (_i, pattern) in patterns.iter().enumerate() {
-        let row_reflections = find_exact_reflections_row(&pattern);
-        let maybe_score = row_reflections
+fn exact_reflection_score(pattern: &Pattern) -> Option<usize> {
+    let row_reflections = find_exact_reflections_row(pattern);
+    let maybe_score = row_reflections
+        .into_iter()
+        .filter(|i| is_complete_row_reflection_from(pattern, *i))
+        .map(|i| (i + 1) * 100)
+        .next_back();
+
+    maybe_score.or_else(|| {
+        let col_reflections = find_exact_reflections_col(pattern);
+        col_reflections
+            .into_iter()
+            .rfind(|i| is_complete_col_reflection_from(pattern, *i))
+            .map(|i| i + 1)
+    })
+}
+
+fn smudged_reflection_score(pattern: &Pattern) -> Option<usize> {
+    let row_reflections = find_almost_exact_reflections_row(pattern);
+    let maybe_score = row_reflections
+        .into_iter()
+        .filter(|i| is_almost_complete_row_reflection_from(pattern, *i))
+        .map(|i| (i + 1) * 100)
+        .find_or_first(|_| true);
+
+    maybe_score.or_else(|| {
+        let col_reflections = find_almost_exact_reflections_col(pattern);
+        col_reflections
             .into_iter()
-            .filter(|i| is_complete_row_reflection_from(pattern, *i))
-            .map(|i| (i + 1) * 100)
-            .last();
-
-        score += match maybe_score {
-            None => {
-                let col_reflections = find_exact_reflections_col(&pattern);
-                let maybe_score = col_reflections
-                    .into_iter()
-                    .filter(|i| is_complete_col_reflection_from(pattern, *i))
-                    .last();
-                maybe_score.unwrap() + 1
-            }
-            Some(score) => score,
-        };
+            .filter(|i| is_almost_complete_col_reflection_from(pattern, *i))
+            .find_or_first(|_| true)
+            .map(|i| i + 1)
+    })
+}
+
+fn check_every_pattern(input: &str, score: fn(&Pattern) -> Option<usize>) -> Result<(), String> {
+    match parse_patterns(input)?.iter().position(|pattern| score(pattern).is_none()) {
+        Some(i) => Err(format!("pattern {} has none", i + 1)),
+        None => Ok(()),
     }
+}
 
-    score.to_string()
+fn every_pattern_reflects(input: &str) -> Result<(), String> {
+    check_every_pattern(input, exact_reflection_score)
 }
 
-pub fn p2(input: &String) -> String {
+fn every_pattern_reflects_with_smudge(input: &str) -> Result<(), String> {
+    check_every_pattern(input, smudged_reflection_score)
+}
+
+pub fn p1(input: &String) -> String {
     let patterns = parse_input(input);
-    let mut score = 0;
-    for (_i, pattern) in patterns.iter().enumerate() {
-        let row_reflections = find_almost_exact_reflections_row(&pattern);
-        let maybe_score = row_reflections
-            .into_iter()
-            .filter(|i| is_almost_complete_row_reflection_from(pattern, *i))
-            .map(|i| (i + 1) * 100)
-            .find_or_first(|_| true);
-
-        score += match maybe_score {
-            None => {
-                let col_reflections = find_almost_exact_reflections_col(&pattern);
-                let maybe_score = col_reflections
-                    .into_iter()
-                    .filter(|i| is_almost_complete_col_reflection_from(pattern, *i))
-                    .find_or_first(|_| true);
-                let score = maybe_score.unwrap() + 1;
-                score
-            }
-            Some(score) => score,
-        };
-    }
+    patterns
+        .iter()
+        .map(|pattern| exact_reflection_score(pattern).unwrap())
+        .sum::<usize>()
+        .to_string()
+}
 
-    score.to_string()
+pub fn p2(input: &String) -> String {
+    let patterns = parse_input(input);
+    patterns
+        .iter()
+        .map(|pattern| smudged_reflection_score(pattern).unwrap())
+        .sum::<usize>()
+        .to_string()
 }
@@ -1,154 +1,96 @@
-use itertools::Itertools;
-use std::collections::HashMap;
+use std::fmt::Display;
 
-type Base = Vec<Vec<char>>;
+use crate::aoccount;
+use crate::grid::Grid;
+use crate::point::Point;
+use crate::sim::{CliObservers, Runner, Simulation};
+use crate::view::ViewMut;
+use crate::zobrist::Fingerprint;
+
+type Base = Grid<char>;
 fn parse_input(input: &String) -> Base {
-    input.lines().map(|line| line.chars().collect_vec()).collect_vec()
+    Grid::parse(input, |c| c)
 }
 
 #[allow(unused)]
 fn print_base(base: &Base) {
-    let base_str = base
-        .iter()
-        .map(|v| v.iter().collect::<String>())
-        .join("\n");
-    println!("base =\n{}", base_str);
+    println!("base =\n{}", base);
 }
 
-fn transpose<T>(v: &Vec<Vec<T>>) -> Vec<Vec<T>>
-where
-    T: Clone,
-{
-    assert!(!v.is_empty());
-    (0..v[0].len())
-        .map(|i| v.iter().map(|inner| inner[i].clone()).collect::<Vec<T>>())
-        .collect()
-}
-
-fn slide_row_left(row: &mut Vec<char>) {
-    let mut i = 0;
-    let mut new_row = vec!['#'; row.len()];
-
-    while i < row.len() {
-        let mut j = i;
-        let mut n = 0;
-        let start_at = i;
-        while j < row.len() && row[j] != '#' {
-            if row[j] == 'O' {
-                n += 1;
+/// Rolls every round rock as far towards `x = 0` as it goes, in whichever
+/// orientation `rocks` looks at the platform, keeping `fingerprint` in step.
+fn tilt(mut rocks: ViewMut<char>, fingerprint: &mut Fingerprint) {
+    for y in 0..rocks.height() as i64 {
+        let mut free = 0;
+        for x in 0..rocks.width() as i64 {
+            match rocks[Point::new(x, y)] {
+                '#' => free = x + 1,
+                'O' => {
+                    if free != x {
+                        rocks.swap(Point::new(x, y), Point::new(free, y));
+                        fingerprint.replace(rocks.to_grid(Point::new(x, y)), &'O', &'.');
+                        fingerprint.replace(rocks.to_grid(Point::new(free, y)), &'.', &'O');
+                    }
+                    free += 1;
+                }
+                _ => (),
             }
-            j += 1;
         }
-
-        for k in start_at..j {
-            if n > 0 {
-                new_row[k] = 'O';
-                n -= 1;
-            } else {
-                new_row[k] = '.';
-            }
-        }
-        if j+1 < row.len() {
-            new_row[j+1] = '#';
-        }
-
-        i = j+1;
     }
+}
 
-    for i in 0..row.len() {
-        row[i] = new_row[i];
-    }
+fn north_load(rocks: &Base) -> usize {
+    rocks
+        .iter()
+        .filter(|(_, &v)| v == 'O')
+        .map(|(p, _)| rocks.height() - p.y as usize)
+        .sum()
 }
 
-fn slide_row_right(row: &mut Vec<char>) {
-    let mut i = row.len();
-    let mut new_row = vec!['#'; row.len()];
+pub fn p1(input: &String) -> String {
+    let mut rocks = parse_input(input);
+    tilt(rocks.view_mut().transposed(), &mut Fingerprint::default());
+    let result = north_load(&rocks);
 
-    while i > 0 {
-        let mut j = i;
-        let mut n = 0;
-        let end_at = i;
-        while j > 0 && row[j - 1] != '#' {
-            if row[j - 1] == 'O' {
-                n += 1;
-            }
-            j -= 1;
-        }
+    result.to_string()
+}
 
-        for k in (j..end_at).rev() {
-            if n > 0 {
-                new_row[k] = 'O';
-                n -= 1;
-            } else {
-                new_row[k] = '.';
-            }
-        }
-        if j > 0 {
-            new_row[j - 1] = '#';
-        }
+/// The rocks with a fingerprint kept up to date as they roll.
+struct Platform {
+    rocks: Base,
+    fingerprint: Fingerprint,
+}
 
-        if j == 0 {
-            break;
-        }
-        i = j - 1;
+impl Simulation for Platform {
+    /// One spin cycle: tilt north, west, south, then east.
+    fn step(&mut self) {
+        let (rocks, fingerprint) = (&mut self.rocks, &mut self.fingerprint);
+        tilt(rocks.view_mut().transposed(), fingerprint);
+        tilt(rocks.view_mut(), fingerprint);
+        tilt(rocks.view_mut().rotated_cw(), fingerprint);
+        tilt(rocks.view_mut().mirrored_x(), fingerprint);
     }
 
-    for i in 0..row.len() {
-        row[i] = new_row[i];
+    fn fingerprint(&self) -> Option<u64> {
+        Some(self.fingerprint.value())
     }
 }
 
-fn row_weight(row: &Vec<char>) -> usize {
-    row.iter().enumerate().map(|(i, &v)| if v == 'O' { row.len() - i} else { 0 }).sum()
-}
-
-pub fn p1(input: &String) -> String {
-    let rocks = parse_input(input);
-    let mut rocks = transpose(&rocks);
-    for i in 0..rocks.len() {
-        slide_row_left(&mut rocks[i]);
+impl Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.rocks)
     }
-    let result = rocks.iter().map(|row| row_weight(row)).sum::<usize>();
-
-    result.to_string()
 }
 
 pub fn p2(input: &String) -> String {
-    let mut rocks = parse_input(input);
-    let mut memo: HashMap<Base, usize> = HashMap::new();
-
-    let mut c = 0;
-    let limit = 1000000000;
-    while c < limit {
-        if let Some(l) = memo.get(&rocks) {
-            let loop_length = c - l;
-            c += ((limit-c)/loop_length) * loop_length;
-            memo.clear();
-            continue;
-        }
-
-        let orig = rocks.clone();
-        rocks = transpose(&rocks);
-        for i in 0..rocks.len() {
-            slide_row_left(&mut rocks[i]);
-        }
-        rocks = transpose(&rocks);
-        for i in 0..rocks.len() {
-            slide_row_left(&mut rocks[i]);
-        }
-        rocks = transpose(&rocks);
-        for i in 0..rocks.len() {
-            slide_row_right(&mut rocks[i]);
-        }
-        rocks = transpose(&rocks);
-        for i in 0..rocks.len() {
-            slide_row_right(&mut rocks[i]);
-        }
-        memo.insert(orig, c);
-        c += 1;
+    let rocks = parse_input(input);
+    let fingerprint = Fingerprint::of_grid(&rocks);
+    let mut observers = CliObservers::new();
+    let mut runner = observers.attach(Runner::new(Platform { rocks, fingerprint }).traced("spin"));
+    if let Some(cycle) = runner.fast_forward(1000000000) {
+        aoccount!("cycle length", cycle.lambda);
     }
 
-    let rocks = transpose(&rocks);
-    let result = rocks.iter().map(|row| row_weight(row)).sum::<usize>();
+    let result = north_load(&runner.state().rocks);
     result.to_string()
 }
@@ -2,6 +2,8 @@ use std::collections::VecDeque;
 
 use itertools::Itertools;
 
+use crate::intern::{Interner, Symbol};
+
 fn hash(current_value: usize, salt: char) -> usize {
     ((current_value + salt as usize) * 17) % 256
 }
@@ -16,29 +18,22 @@ pub fn p1(input: &String) -> String {
 }
 
 pub fn p2(input: &String) -> String {
-    let mut boxes = vec![VecDeque::<(String, usize)>::new(); 256];
+    let mut labels = Interner::new();
+    let mut boxes = vec![VecDeque::<(Symbol, usize)>::new(); 256];
     input
         .split(",")
         .map(|line| {
-            let line = line.chars().filter(|c| *c != '\n').collect::<String>();
-            if line.contains('=') {
-                let (left, right) = line
-                    .split('=')
-                    .map(|s| s.to_owned())
-                    .collect_tuple()
-                    .unwrap();
+            let line = line.trim();
+            if let Some((left, right)) = line.split_once('=') {
                 (left, right.parse::<usize>().unwrap(), '=')
             } else {
-                let (left, _) = line
-                    .split('-')
-                    .map(|s| s.to_owned())
-                    .collect_tuple()
-                    .unwrap();
+                let (left, _) = line.split_once('-').unwrap();
                 (left, 0usize, '-')
             }
         })
         .for_each(|(label, focal, op)| {
             let box_number = label.chars().fold(0, |acc, v| hash(acc, v));
+            let label = labels.intern(label);
             match op {
                 '=' => {
                     let any = boxes[box_number].iter().find_position(|(s, _)| *s == label);
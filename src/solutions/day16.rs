@@ -1,143 +1,132 @@
-use itertools::Itertools;
+use std::fmt::Display;
 
-type Field = Vec<Vec<char>>;
-type FieldEnergy = Vec<Vec<u8>>;
+use crate::aoccount;
+use crate::bitgrid::BitGrid;
+use crate::grid::Grid;
+use crate::parallel;
+use crate::point::{Direction, Point};
+use crate::sim::{CliObservers, Runner, Simulation};
+
+type Field = Grid<char>;
+/// Cells a beam has passed through, one layer per heading.
+type FieldEnergy = [BitGrid; 4];
 
 fn parse_input(input: &String) -> Field {
-    input
-        .lines()
-        .map(|line| line.chars().collect_vec())
-        .collect_vec()
+    Grid::parse(input, |c| c)
 }
 
 #[allow(unused)]
 fn print_field(f: &Field) {
-    let f_str = f.iter().map(|v| v.iter().collect::<String>()).join("\n");
-    println!("{}", f_str);
+    println!("{}", f);
 }
 
-#[derive(Debug)]
-enum Dir {
-    Up,
-    Down,
-    Right,
-    Left,
+/// Where a beam heading `dir` goes after bouncing off a `\` or `/` mirror.
+fn reflect(mirror: char, dir: Direction) -> Direction {
+    match (mirror, dir.is_horizontal()) {
+        ('\\', true) | ('/', false) => dir.cw(),
+        _ => dir.ccw(),
+    }
 }
 
-impl Dir {
-    fn offset(&self) -> (i32, i32) {
-        use Dir::*;
-        match self {
-            Up => (0, -1),
-            Down => (0, 1),
-            Right => (1, 0),
-            Left => (-1, 0),
-        }
-    }
+/// Beams moving through the field together, each step advances every beam
+/// front by one cell and drops the ones going where a beam already went.
+struct Beams<'a> {
+    field: &'a Field,
+    energy: FieldEnergy,
+    front: Vec<(Point, Direction)>,
+}
 
-    fn rotate_90_ccw(&self) -> Dir {
-        use Dir::*;
-        match self {
-            Up => Left,
-            Down => Right,
-            Right => Down,
-            Left => Up,
+impl<'a> Beams<'a> {
+    /// A single beam about to enter the field from `start`.
+    fn new(field: &'a Field, start: Point, dir: Direction) -> Self {
+        Beams {
+            field,
+            energy: std::array::from_fn(|_| BitGrid::new(field.width(), field.height())),
+            front: vec![(start, dir)],
         }
     }
 
-    fn rotate_90_cw(&self) -> Dir {
-        use Dir::*;
-        match self {
-            Up => Right,
-            Down => Left,
-            Right => Up,
-            Left => Down,
-        }
+    fn energised(&self) -> BitGrid {
+        let (width, height) = (self.field.width(), self.field.height());
+        self.energy
+            .iter()
+            .fold(BitGrid::new(width, height), |all, layer| &all | layer)
     }
+}
 
-    fn vertical(&self) -> bool {
-        use Dir::*;
-        match self {
-            Up | Down => true,
-            _ => false,
+impl Simulation for Beams<'_> {
+    fn step(&mut self) {
+        let mut front = Vec::new();
+        for (pos, dir) in std::mem::take(&mut self.front) {
+            let pos = pos + dir;
+            let Some(&tile) = self.field.get(pos) else {
+                continue;
+            };
+            if !self.energy[dir as usize].insert(pos) {
+                continue;
+            }
+            match tile {
+                '|' if dir.is_horizontal() => {
+                    front.extend([(pos, Direction::Down), (pos, Direction::Up)])
+                }
+                '-' if dir.is_vertical() => {
+                    front.extend([(pos, Direction::Left), (pos, Direction::Right)])
+                }
+                '.' | '|' | '-' => front.push((pos, dir)),
+                '\\' | '/' => front.push((pos, reflect(tile, dir))),
+                _ => unreachable!(),
+            }
         }
+        self.front = front;
     }
 
-    fn horizontal(&self) -> bool {
-        !self.vertical()
-    }
-
-    fn flag(&self) -> u8 {
-        use Dir::*;
-        match self {
-            Up => 0b0001,
-            Down => 0b0010,
-            Right => 0b0100,
-            Left => 0b1000,
-        }
+    fn is_done(&self) -> bool {
+        self.front.is_empty()
     }
 }
 
-fn shoot_beam((x, y): (i32, i32), dir: Dir, field: &Field, field_energy: &mut FieldEnergy) {
-    let (ox, oy) = dir.offset();
-    let (x, y) = (ox + x, oy + y);
-    if x < 0 || x as usize >= field[0].len() || y < 0 || y as usize >= field.len() {
-        return;
-    }
-    let (xu, yu) = (x as usize, y as usize);
-    let dir_flag = dir.flag();
-    if field_energy[yu][xu] & dir_flag > 0 {
-        return;
-    }
-    field_energy[yu][xu] |= dir_flag;
-
-    let pos = (x, y);
-    match field[yu][xu] {
-        '.' => shoot_beam(pos, dir, field, field_energy),
-        '|' => {
-            if dir.vertical() {
-                shoot_beam(pos, dir, field, field_energy)
-            } else {
-                shoot_beam(pos, Dir::Down, field, field_energy);
-                shoot_beam(pos, Dir::Up, field, field_energy)
-            }
-        }
-        '-' => {
-            if dir.horizontal() {
-                shoot_beam(pos, dir, field, field_energy)
-            } else {
-                shoot_beam(pos, Dir::Left, field, field_energy);
-                shoot_beam(pos, Dir::Right, field, field_energy)
-            }
-        }
-        '\\' => shoot_beam(pos, dir.rotate_90_ccw(), field, field_energy),
-        '/' => shoot_beam(pos, dir.rotate_90_cw(), field, field_energy),
-        _ => unreachable!(),
+impl Display for Beams<'_> {
+    /// Energised cells as `#` over the field.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let energised = self.energised();
+        let shown = self
+            .field
+            .iter()
+            .map(|(p, &c)| if energised.get(p) { '#' } else { c })
+            .collect();
+        write!(f, "{}", Grid::from_vec(self.field.width(), shown))
     }
 }
 
-fn solve_from(field: &Field, start: (i32, i32), dir: Dir) -> usize {
-    let mut fe = vec![vec![0u8; field[0].len()]; field.len()];
-    shoot_beam(start, dir, &field, &mut fe);
-    fe.iter()
-        .flat_map(|row| row.iter())
-        .filter(|v| **v > 0)
-        .count()
+/// Runs the beams until none are left, returns how many cells they energised.
+fn energise(mut runner: Runner<Beams>) -> usize {
+    runner.run_until_done();
+    let energised = runner.state().energised().count_ones();
+    aoccount!("beam starts");
+    aoccount!("cells energised", energised);
+    energised
 }
 
 pub fn p1(input: &String) -> String {
     let field = parse_input(input);
-    solve_from(&field, (-1, 0), Dir::Right).to_string()
+    let mut observers = CliObservers::new();
+    let beams = Beams::new(&field, Point::new(-1, 0), Direction::Right);
+    energise(observers.attach(Runner::new(beams))).to_string()
 }
 
 pub fn p2(input: &String) -> String {
     let field = parse_input(input);
-    (0..field[0].len())
-        .map(|v| ((v as i32, -1i32), Dir::Down))
-        .chain((0..field[0].len()).map(|v| ((v as i32, field.len() as i32), Dir::Up)))
-        .chain((0..field.len()).map(|v| ((-1, v as i32), Dir::Right)))
-        .chain((0..field.len()).map(|v| ((field[0].len() as i32, v as i32), Dir::Left)))
-        .map(|(start, dir)| solve_from(&field, start, dir))
+    let (width, height) = (field.width() as i64, field.height() as i64);
+    let starts = (0..width)
+        .map(|v| (Point::new(v, -1), Direction::Down))
+        .chain((0..width).map(|v| (Point::new(v, height), Direction::Up)))
+        .chain((0..height).map(|v| (Point::new(-1, v), Direction::Right)))
+        .chain((0..height).map(|v| (Point::new(width, v), Direction::Left)))
+        .collect::<Vec<_>>();
+    let energise_from =
+        |&(start, dir): &(Point, Direction)| energise(Runner::new(Beams::new(&field, start, dir)));
+    parallel::map(&starts, energise_from)
+        .into_iter()
         .max()
         .unwrap()
         .to_string()
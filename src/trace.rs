@@ -0,0 +1,99 @@
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering::Relaxed};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+static EVENTS: Mutex<Vec<Event>> = Mutex::new(Vec::new());
+static NEXT_TID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static TID: u64 = NEXT_TID.fetch_add(1, Relaxed);
+}
+
+struct Event {
+    name: Cow<'static, str>,
+    tid: u64,
+    start_us: f64,
+    duration_us: f64,
+}
+
+/// Guard returned by [`span`], records the elapsed time when dropped.
+pub struct Span {
+    name: Cow<'static, str>,
+    start: Option<Instant>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let epoch = *EPOCH.get_or_init(Instant::now);
+        let event = Event {
+            name: std::mem::take(&mut self.name),
+            tid: TID.with(|tid| *tid),
+            start_us: start.saturating_duration_since(epoch).as_secs_f64() * 1e6,
+            duration_us: start.elapsed().as_secs_f64() * 1e6,
+        };
+        EVENTS.lock().unwrap().push(event);
+    }
+}
+
+/// Starts recording spans, until then [`span`] is a no-op.
+pub fn enable() {
+    EPOCH.get_or_init(Instant::now);
+    ENABLED.store(true, Relaxed);
+}
+
+/// Times the enclosing scope under `name`. Bind the result to a named
+/// variable (`let _span = ...`), `let _ = ...` drops it immediately.
+pub fn span(name: impl Into<Cow<'static, str>>) -> Span {
+    Span {
+        name: name.into(),
+        start: ENABLED.load(Relaxed).then(Instant::now),
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Writes every recorded span as complete ("X") events of the Chrome
+/// trace-event format, loadable by chrome://tracing or Perfetto.
+pub fn write_chrome_trace(path: &str) -> std::io::Result<()> {
+    let events = EVENTS.lock().unwrap();
+    let entries = events
+        .iter()
+        .map(|e| {
+            format!(
+                "{{\"name\":\"{}\",\"cat\":\"aoc\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\"pid\":1,\"tid\":{}}}",
+                escape_json(&e.name),
+                e.start_us,
+                e.duration_us,
+                e.tid
+            )
+        })
+        .collect::<Vec<_>>();
+    std::fs::write(
+        path,
+        format!("{{\"traceEvents\":[\n{}\n]}}\n", entries.join(",\n")),
+    )
+}
+
+#[macro_export]
+macro_rules! aocspan {
+    ($name:expr) => {
+        $crate::trace::span($name)
+    };
+}
@@ -0,0 +1,7 @@
+/// A property of the puzzle input that a solution relies on without checking.
+/// `check` explains how the input violates it, so a wrong answer can be traced
+/// back to the input rather than to the solution.
+pub struct Assumption {
+    pub description: &'static str,
+    pub check: fn(&str) -> Result<(), String>,
+}
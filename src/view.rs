@@ -0,0 +1,421 @@
+use std::ops::{Index, IndexMut};
+
+use crate::grid::Grid;
+use crate::point::Point;
+
+/// One of the eight ways to lay a rectangle back on itself. A view position
+/// is first mirrored along the requested axes of the view, then transposed,
+/// which lands on the underlying grid position.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    transpose: bool,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+impl Orientation {
+    pub const IDENTITY: Orientation = Orientation {
+        transpose: false,
+        flip_x: false,
+        flip_y: false,
+    };
+
+    /// Swaps rows and columns of whatever this orientation already shows.
+    pub fn transposed(self) -> Self {
+        Orientation {
+            transpose: !self.transpose,
+            flip_x: self.flip_y,
+            flip_y: self.flip_x,
+        }
+    }
+
+    /// Left-right mirror image.
+    pub fn mirrored_x(self) -> Self {
+        Orientation {
+            flip_x: !self.flip_x,
+            ..self
+        }
+    }
+
+    /// Upside-down mirror image.
+    pub fn mirrored_y(self) -> Self {
+        Orientation {
+            flip_y: !self.flip_y,
+            ..self
+        }
+    }
+
+    pub fn rotated_cw(self) -> Self {
+        self.transposed().mirrored_x()
+    }
+
+    pub fn rotated_ccw(self) -> Self {
+        self.transposed().mirrored_y()
+    }
+
+    pub fn rotated_180(self) -> Self {
+        self.mirrored_x().mirrored_y()
+    }
+
+    /// Width and height seen through this orientation of a `width` x `height` grid.
+    pub fn dims(&self, width: usize, height: usize) -> (usize, usize) {
+        if self.transpose {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Position on a `width` x `height` grid shown at `p` through this orientation.
+    pub fn to_grid(&self, p: Point, width: usize, height: usize) -> Point {
+        let (view_width, view_height) = self.dims(width, height);
+        let x = if self.flip_x { view_width as i64 - 1 - p.x } else { p.x };
+        let y = if self.flip_y { view_height as i64 - 1 - p.y } else { p.y };
+        if self.transpose {
+            Point::new(y, x)
+        } else {
+            Point::new(x, y)
+        }
+    }
+}
+
+/// Read-only window on a [`Grid`] in some [`Orientation`], positions are
+/// translated on every access so nothing gets copied until [`View::materialize`].
+pub struct View<'a, T> {
+    grid: &'a Grid<T>,
+    orientation: Orientation,
+}
+
+impl<T> Clone for View<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for View<'_, T> {}
+
+impl<'a, T> View<'a, T> {
+    pub fn new(grid: &'a Grid<T>, orientation: Orientation) -> Self {
+        View { grid, orientation }
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    pub fn transposed(self) -> Self {
+        View::new(self.grid, self.orientation.transposed())
+    }
+
+    pub fn mirrored_x(self) -> Self {
+        View::new(self.grid, self.orientation.mirrored_x())
+    }
+
+    pub fn mirrored_y(self) -> Self {
+        View::new(self.grid, self.orientation.mirrored_y())
+    }
+
+    pub fn rotated_cw(self) -> Self {
+        View::new(self.grid, self.orientation.rotated_cw())
+    }
+
+    pub fn rotated_ccw(self) -> Self {
+        View::new(self.grid, self.orientation.rotated_ccw())
+    }
+
+    pub fn rotated_180(self) -> Self {
+        View::new(self.grid, self.orientation.rotated_180())
+    }
+
+    pub fn width(&self) -> usize {
+        self.orientation.dims(self.grid.width(), self.grid.height()).0
+    }
+
+    pub fn height(&self) -> usize {
+        self.orientation.dims(self.grid.width(), self.grid.height()).1
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width() && (p.y as usize) < self.height()
+    }
+
+    pub fn get(&self, p: Point) -> Option<&'a T> {
+        if !self.contains(p) {
+            return None;
+        }
+        self.grid
+            .get(self.orientation.to_grid(p, self.grid.width(), self.grid.height()))
+    }
+
+    pub fn row(&self, y: usize) -> Line<'a, T> {
+        Line {
+            view: *self,
+            next: Point::new(0, y as i64),
+            step: Point::new(1, 0),
+            remaining: self.width(),
+        }
+    }
+
+    pub fn column(&self, x: usize) -> Line<'a, T> {
+        Line {
+            view: *self,
+            next: Point::new(x as i64, 0),
+            step: Point::new(0, 1),
+            remaining: self.height(),
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = Line<'a, T>> + '_ {
+        (0..self.height()).map(|y| self.row(y))
+    }
+
+    /// Every position of the view, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width() as i64, self.height() as i64);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &'a T)> {
+        let view = *self;
+        self.points().map(move |p| (p, view.get(p).unwrap()))
+    }
+
+    /// Copies the view into a grid laid out the way it is seen.
+    pub fn materialize(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_vec(self.width(), self.iter().map(|(_, v)| v.clone()).collect())
+    }
+}
+
+impl<T> Index<Point> for View<'_, T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p).unwrap_or_else(|| {
+            panic!("{:?} is outside of a {}x{} view", p, self.width(), self.height())
+        })
+    }
+}
+
+/// A row or column of a [`View`], walked lazily.
+pub struct Line<'a, T> {
+    view: View<'a, T>,
+    next: Point,
+    step: Point,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Line<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let value = self.view.get(self.next)?;
+        self.next += self.step;
+        self.remaining -= 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Line<'_, T> {}
+
+/// Mutable counterpart of [`View`], writes go straight to the underlying grid.
+pub struct ViewMut<'a, T> {
+    grid: &'a mut Grid<T>,
+    orientation: Orientation,
+}
+
+impl<'a, T> ViewMut<'a, T> {
+    pub fn new(grid: &'a mut Grid<T>, orientation: Orientation) -> Self {
+        ViewMut { grid, orientation }
+    }
+
+    pub fn as_view(&self) -> View<'_, T> {
+        View::new(self.grid, self.orientation)
+    }
+
+    pub fn transposed(self) -> Self {
+        ViewMut::new(self.grid, self.orientation.transposed())
+    }
+
+    pub fn mirrored_x(self) -> Self {
+        ViewMut::new(self.grid, self.orientation.mirrored_x())
+    }
+
+    pub fn mirrored_y(self) -> Self {
+        ViewMut::new(self.grid, self.orientation.mirrored_y())
+    }
+
+    pub fn rotated_cw(self) -> Self {
+        ViewMut::new(self.grid, self.orientation.rotated_cw())
+    }
+
+    pub fn rotated_ccw(self) -> Self {
+        ViewMut::new(self.grid, self.orientation.rotated_ccw())
+    }
+
+    pub fn rotated_180(self) -> Self {
+        ViewMut::new(self.grid, self.orientation.rotated_180())
+    }
+
+    pub fn width(&self) -> usize {
+        self.as_view().width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.as_view().height()
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.as_view().get(p)
+    }
+
+    /// Position on the underlying grid shown at `p`.
+    pub fn to_grid(&self, p: Point) -> Point {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        self.orientation.to_grid(p, width, height)
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        if !self.as_view().contains(p) {
+            return None;
+        }
+        let (width, height) = (self.grid.width(), self.grid.height());
+        self.grid.get_mut(self.orientation.to_grid(p, width, height))
+    }
+
+    /// Swaps the values at two positions of the view.
+    pub fn swap(&mut self, a: Point, b: Point) {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let a = self.orientation.to_grid(a, width, height);
+        let b = self.orientation.to_grid(b, width, height);
+        self.grid.swap(a, b);
+    }
+
+    pub fn materialize(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.as_view().materialize()
+    }
+}
+
+impl<T> Index<Point> for ViewMut<'_, T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p).unwrap_or_else(|| {
+            panic!("{:?} is outside of a {}x{} view", p, self.width(), self.height())
+        })
+    }
+}
+
+impl<T> IndexMut<Point> for ViewMut<'_, T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        let (width, height) = (self.width(), self.height());
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{:?} is outside of a {}x{} view", p, width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3 wide and 2 high, so a mixed up width and height shows.
+    fn grid() -> Grid<char> {
+        Grid::parse("abc\ndef", |c| c)
+    }
+
+    fn rows(view: View<'_, char>) -> Vec<String> {
+        view.rows().map(|row| row.collect()).collect()
+    }
+
+    #[test]
+    fn orientations_of_a_non_square_grid() {
+        let grid = grid();
+        let view = grid.view();
+        assert_eq!(rows(view), ["abc", "def"]);
+        assert_eq!(rows(view.transposed()), ["ad", "be", "cf"]);
+        assert_eq!(rows(view.rotated_cw()), ["da", "eb", "fc"]);
+        assert_eq!(rows(view.rotated_ccw()), ["cf", "be", "ad"]);
+        assert_eq!(rows(view.rotated_180()), ["fed", "cba"]);
+        assert_eq!(rows(view.mirrored_x()), ["cba", "fed"]);
+        assert_eq!(rows(view.mirrored_y()), ["def", "abc"]);
+        assert_eq!(
+            (view.rotated_cw().width(), view.rotated_cw().height()),
+            (2, 3)
+        );
+        assert_eq!(view.rotated_cw().column(1).collect::<String>(), "abc");
+        assert_eq!(view.rotated_cw().get(Point::new(2, 0)), None);
+    }
+
+    #[test]
+    fn to_grid_maps_view_positions_to_grid_cells() {
+        let (width, height) = (3, 2);
+        let id = Orientation::IDENTITY;
+        let cases = [
+            (id, Point::new(2, 1), Point::new(2, 1)),
+            (id.transposed(), Point::new(1, 2), Point::new(2, 1)),
+            (id.transposed(), Point::new(0, 1), Point::new(1, 0)),
+            (id.rotated_cw(), Point::new(0, 0), Point::new(0, 1)),
+            (id.rotated_cw(), Point::new(1, 2), Point::new(2, 0)),
+            (id.rotated_cw(), Point::new(0, 2), Point::new(2, 1)),
+            (id.mirrored_x(), Point::new(0, 0), Point::new(2, 0)),
+            (id.mirrored_x(), Point::new(2, 1), Point::new(0, 1)),
+        ];
+        for (orientation, view, expected) in cases {
+            assert_eq!(
+                orientation.to_grid(view, width, height),
+                expected,
+                "{:?}",
+                orientation
+            );
+        }
+        let grid = grid();
+        for (orientation, view, expected) in cases {
+            assert_eq!(View::new(&grid, orientation)[view], grid[expected]);
+        }
+    }
+
+    #[test]
+    fn orientations_compose() {
+        let id = Orientation::IDENTITY;
+        assert_eq!(id.rotated_cw().rotated_ccw(), id);
+        assert_eq!(id.rotated_cw().rotated_cw(), id.rotated_180());
+        assert_eq!(id.rotated_180().rotated_180(), id);
+        assert_eq!(id.transposed().transposed(), id);
+        assert_eq!(id.mirrored_x().mirrored_y(), id.rotated_180());
+    }
+
+    #[test]
+    fn view_mut_writes_through_to_the_grid() {
+        let mut grid = grid();
+        let mut view = grid.view_mut().rotated_cw();
+        assert_eq!(view.to_grid(Point::new(1, 2)), Point::new(2, 0));
+        // d and c end up in opposite corners of the rotated view.
+        view.swap(Point::new(0, 0), Point::new(1, 2));
+        assert_eq!(rows(view.as_view()), ["ca", "eb", "fd"]);
+        view[Point::new(1, 1)] = 'x';
+        *view.get_mut(Point::new(0, 2)).unwrap() = 'y';
+        assert_eq!(view.get_mut(Point::new(2, 0)), None);
+        assert_eq!(grid.to_string(), "axd\ncey");
+    }
+
+    #[test]
+    fn materialize_copies_the_view_layout() {
+        let grid = grid();
+        let turned = grid.view().rotated_ccw().materialize();
+        assert_eq!((turned.width(), turned.height()), (2, 3));
+        assert_eq!(turned.to_string(), "cf\nbe\nad");
+        assert_eq!(turned.view().rotated_cw().materialize(), grid);
+    }
+}
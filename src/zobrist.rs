@@ -0,0 +1,78 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::grid::Grid;
+use crate::point::Point;
+
+/// Random-looking key for `value` sitting at `p`. `DefaultHasher::new` always
+/// starts from the same keys, so this is stable across runs.
+fn key<T: Hash>(p: Point, value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (p, value).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Zobrist-style fingerprint of a grid: the xor of one key per cell and value.
+/// Changing a cell only touches its two keys, so it can be kept up to date in
+/// O(1) per change instead of rehashing the whole grid.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fingerprint(u64);
+
+impl Fingerprint {
+    pub fn of_grid<T: Hash>(grid: &Grid<T>) -> Self {
+        let mut fingerprint = Fingerprint::default();
+        for (p, value) in grid.iter() {
+            fingerprint.toggle(p, value);
+        }
+        fingerprint
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// Adds `value` at `p` if it isn't accounted for, removes it if it is.
+    pub fn toggle<T: Hash>(&mut self, p: Point, value: &T) {
+        self.0 ^= key(p, value);
+    }
+
+    /// Accounts for the cell at `p` changing from `old` to `new`.
+    pub fn replace<T: Hash>(&mut self, p: Point, old: &T, new: &T) {
+        self.toggle(p, old);
+        self.toggle(p, new);
+    }
+}
+
+/// Remembers at which step each fingerprint was first seen, and nothing else
+/// about the states, so a long run costs a few words per step.
+#[derive(Debug, Default, Clone)]
+pub struct StateStore {
+    seen: HashMap<u64, usize>,
+}
+
+impl StateStore {
+    pub fn new() -> Self {
+        StateStore::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    /// Records `fingerprint` at `step`, returns the earlier step it was seen
+    /// at, if any. The first step stays recorded.
+    pub fn record(&mut self, fingerprint: u64, step: usize) -> Option<usize> {
+        match self.seen.get(&fingerprint) {
+            Some(&first) => Some(first),
+            None => {
+                self.seen.insert(fingerprint, step);
+                None
+            }
+        }
+    }
+}